@@ -0,0 +1,1083 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use super::super::parser::ast::*;
+use super::super::semantic::mangle::{mangle, vtable};
+use super::super::semantic::semantic::{
+    FunctionKind, FunctionSymbol, SymbolTable, LIST_LEN, LIST_POP, LIST_PUSH,
+};
+
+use crate::code_generator::frame::{arg_locations, value_size, ArgLocation};
+use crate::code_generator::layout::{field_offset, object_size, vtable_offset};
+use crate::code_generator::CodeGenerator;
+
+mod runtime;
+
+// The registers used to pass the first arguments of a function (AAPCS64), for integer and for
+// floating point arguments. The rest of the arguments are passed on the stack.
+const ARG_REGISTERS: [&str; 8] = ["x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7"];
+const FLOAT_ARG_REGISTERS: [&str; 8] = ["d0", "d1", "d2", "d3", "d4", "d5", "d6", "d7"];
+
+// The instruction selection for AArch64 is the same on every operating system. The only things
+// that differ are the entry point, how syscalls are made, and how symbols are named. Those are
+// provided by the operating system through this trait.
+pub trait Aarch64Os {
+    /// The symbol the linker uses as the entry point of the executable.
+    const ENTRY_SYMBOL: &'static str;
+    /// The register that holds the number of the syscall to make.
+    const SYSCALL_REGISTER: &'static str;
+    /// The immediate passed to `svc` when making a syscall.
+    const SYSCALL_IMMEDIATE: &'static str;
+    /// The number of the exit syscall.
+    const EXIT_SYSCALL: u32;
+    /// The number of the write syscall.
+    const WRITE_SYSCALL: u32;
+    /// The number of the mmap syscall.
+    const MMAP_SYSCALL: u32;
+    /// The mmap flag that maps memory which is not backed by a file.
+    const MAP_ANONYMOUS: u32;
+    /// The directive that switches to the section of read-only data.
+    const RODATA_SECTION: &'static str;
+
+    /// Turns the name of a function into the symbol that is emitted for it.
+    fn symbol(name: &str) -> String;
+    /// Turns the name of a label into an assembler local label (one that is not a symbol).
+    fn local_label(name: &str) -> String;
+    /// Turns the name of a label of data into a label that is private to the object file.
+    fn data_label(name: &str) -> String;
+    /// Loads the address of a data label into a register.
+    fn load_address(register: &str, label: &str) -> Vec<String>;
+    /// Branches to a label when the syscall that was just made failed.
+    fn branch_if_failed(label: &str) -> Vec<String>;
+}
+
+pub struct Aarch64<Os: Aarch64Os> {
+    symbols: SymbolTable,
+    // The stack slot of every variable of the function being generated, by the span of the name
+    // of the variable in its declaration.
+    slots: HashMap<Span, i32>,
+    current_offset: i32,
+    // The deepest the slots of the function being generated got, which is what its frame reserves.
+    frame_size: i32,
+    // The slot of `this`, when the function being generated is a constructor.
+    this_offset: i32,
+    // The label of the epilogue of the function being generated, which every return jumps to.
+    epilogue_label: String,
+    label_count: usize,
+    // The head and exit labels of the loops we are in (the innermost loop is the last one). These
+    // are where `continue` and `break` jump to.
+    loop_labels: Vec<(String, String)>,
+    // The label and the bytes of every string literal of the program, which are emitted as
+    // read-only data after the code.
+    strings: Vec<(String, Vec<u8>)>,
+    os: PhantomData<Os>,
+}
+
+impl<Os: Aarch64Os> CodeGenerator for Aarch64<Os> {
+    fn generate(&mut self, prog: &NodeProg, symbols: SymbolTable) -> String {
+        let mut lines = vec![
+            format!(".global {}", Os::ENTRY_SYMBOL),
+            format!("{}:", Os::ENTRY_SYMBOL),
+            format!("    bl {}", Os::symbol(&mangle(None, "main", &[]))),
+            // The exit code is already in x0, since it is the return value of main.
+            format!("    mov {}, #{}", Os::SYSCALL_REGISTER, Os::EXIT_SYSCALL),
+            format!("    svc {}", Os::SYSCALL_IMMEDIATE),
+        ];
+        self.symbols = symbols;
+        for class in prog.classes.iter() {
+            for method in class.constructors.iter().chain(class.methods.iter()) {
+                self.generate_function(&method.func, &mut lines);
+            }
+        }
+        for function in prog.functions.iter() {
+            self.generate_function(function, &mut lines);
+        }
+        self.generate_vtables(prog, &mut lines);
+        runtime::generate_runtime::<Os>(&mut lines, &mut self.strings);
+        self.generate_strings(&mut lines);
+        lines.join("\n")
+    }
+}
+
+impl<Os: Aarch64Os> Aarch64<Os> {
+    pub fn new() -> Self {
+        Aarch64 {
+            symbols: SymbolTable::default(),
+            slots: HashMap::new(),
+            current_offset: 0,
+            frame_size: 0,
+            this_offset: 0,
+            epilogue_label: String::new(),
+            label_count: 0,
+            loop_labels: Vec::new(),
+            strings: Vec::new(),
+            os: PhantomData,
+        }
+    }
+
+    // Creates a label that is unique in the whole program.
+    fn new_label(&mut self, name: &str) -> String {
+        self.label_count += 1;
+        Os::local_label(&format!("{}_{}", name, self.label_count))
+    }
+
+    // Emits every string literal, with its length (8 bytes) followed by its bytes.
+    fn generate_strings(&mut self, lines: &mut Vec<String>) {
+        if self.strings.is_empty() {
+            return;
+        }
+        lines.push(Os::RODATA_SECTION.to_string());
+        lines.push(".p2align 3".to_string());
+        for (label, bytes) in self.strings.iter() {
+            lines.push(format!("{}:", label));
+            lines.push(format!("    .quad {}", bytes.len()));
+            if !bytes.is_empty() {
+                let bytes: Vec<String> = bytes.iter().map(|byte| byte.to_string()).collect();
+                lines.push(format!("    .byte {}", bytes.join(", ")));
+            }
+            // Keeps the next length aligned.
+            lines.push(".p2align 3".to_string());
+        }
+    }
+
+    // Emits the vtable of every class, with the address of each of its methods. Every object
+    // starts with the address of the vtable of its class, so calling a method through it calls
+    // the method of the class of the object, even when only a base class of it is known.
+    fn generate_vtables(&self, prog: &NodeProg, lines: &mut Vec<String>) {
+        if prog.classes.is_empty() {
+            return;
+        }
+        lines.push(".data".to_string());
+        lines.push(".p2align 3".to_string());
+        for class in prog.classes.iter() {
+            lines.push(format!("{}:", Os::symbol(&vtable(&class.ident.name))));
+            for method in self.symbols.class(&class.ident.name).vtable.iter() {
+                lines.push(format!("    .quad {}", Os::symbol(&method.symbol)));
+            }
+        }
+        lines.push(".text".to_string());
+    }
+
+    // Hands out the next free stack slot of the frame.
+    fn alloc_slot(&mut self) -> i32 {
+        self.alloc_region(8)
+    }
+
+    // Hands out the next size bytes of the frame (for the elements of an array).
+    fn alloc_region(&mut self, size: i32) -> i32 {
+        self.current_offset -= size;
+        self.frame_size = self.frame_size.max(-self.current_offset);
+        self.current_offset
+    }
+
+    // Finds the stack slot of the variable an identifier refers to.
+    fn slot(&self, ident: &NodeIdent) -> i32 {
+        self.slots[&self.symbols.declaration(ident)]
+    }
+
+    fn generate_function(&mut self, func: &NodeFunc, lines: &mut Vec<String>) {
+        let function = self.symbols.function(&func.ident);
+        let func_name = Os::symbol(&function.symbol);
+        // A constructor is given the object it initializes as a hidden first argument.
+        let this_type = function
+            .kind
+            .class()
+            .map(|class| TypeMeta::Class(class.to_string()));
+        let constructor = matches!(function.kind, FunctionKind::Constructor(_));
+        let func_global = format!(".global {}", func_name);
+        let func_header = format!("{}:", func_name);
+        lines.push(func_global);
+        lines.push(func_header);
+        // STP (store pair) of x29 and x30 to the stack. Each register is 8 bytes.
+        lines.push("    stp x29, x30, [sp, #-16]!".to_string());
+        // Set the frame pointer to the current stack pointer.
+        lines.push("    mov x29, sp".to_string());
+        let locations = arg_locations(
+            this_type
+                .iter()
+                .chain(func.params.iter().map(|param| &param.p_type.meta)),
+            ARG_REGISTERS.len(),
+            FLOAT_ARG_REGISTERS.len(),
+        );
+        // The body is generated first, since the size of the frame is only known once every slot
+        // of the function has been handed out.
+        let mut body = Vec::new();
+        // Reset the current offset (and the deepest it got) to 0.
+        self.current_offset = 0;
+        self.frame_size = 0;
+        self.epilogue_label = self.new_label("epilogue");
+        // The parameters passed in registers are stored in stack slots, while the ones passed on the stack are used where
+        // the caller put them (right above the saved x29 and x30).
+        self.slots.clear();
+        let mut locations = locations.into_iter();
+        if this_type.is_some() {
+            // The object is the first argument, so it is always in the first register.
+            locations.next();
+            self.this_offset = self.alloc_slot();
+            access_slot("str", ARG_REGISTERS[0], self.this_offset, &mut body);
+        }
+        for (param, location) in func.params.iter().zip(locations) {
+            let offset = match location {
+                ArgLocation::IntRegister(idx) => {
+                    let offset = self.alloc_slot();
+                    access_slot("str", ARG_REGISTERS[idx], offset, &mut body);
+                    offset
+                }
+                ArgLocation::FloatRegister(idx) => {
+                    let offset = self.alloc_slot();
+                    access_slot("str", FLOAT_ARG_REGISTERS[idx], offset, &mut body);
+                    offset
+                }
+                ArgLocation::Stack(idx) => 16 + 8 * idx as i32,
+            };
+            self.slots.insert(param.ident.span, offset);
+        }
+        // Arrays are passed as the address of their elements, which are copied into the frame so
+        // that the function cannot change the array of the caller.
+        for param in func.params.iter() {
+            if !param.p_type.meta.is_array() {
+                continue;
+            }
+            let size = value_size(&param.p_type.meta);
+            let offset = self.alloc_region(size);
+            access_slot("ldr", "x9", self.slots[&param.ident.span], &mut body);
+            slot_address("x10", offset, &mut body);
+            self.copy_value(size, &mut body);
+            self.slots.insert(param.ident.span, offset);
+        }
+        // Then, generate the function body (block).
+        self.generate_block(&func.block, &mut body);
+        // A constructor returns the object it initialized.
+        if constructor {
+            access_slot("ldr", "x0", self.this_offset, &mut body);
+        }
+        // Now reserve the space for the deepest the slots got, rounded up to 16 bytes since sp has
+        // to stay 16 byte aligned. The slots are addressed relative to x29, and sp stays below
+        // them until the function returns. SUB can only encode 12 bit immediates, so bigger frames
+        // (with arrays) are reserved through a register.
+        let frame_size = (self.frame_size + 15) / 16 * 16;
+        if frame_size > 0xfff {
+            move_immediate("x9", frame_size as i64, lines);
+            lines.push("    sub sp, sp, x9".to_string());
+        } else if frame_size > 0 {
+            lines.push(format!("    sub sp, sp, #{}", frame_size));
+        }
+        lines.append(&mut body);
+        // Finally, the epilogue. Every return jumps here with the return value already in x0 (or d0
+        // for floats).
+        lines.push(format!("{}:", self.epilogue_label));
+        // Free the frame, then LDP (load pair) of x29 and x30 from the stack. Each register is 8
+        // bytes.
+        lines.push("    mov sp, x29".to_string());
+        lines.push("    ldp x29, x30, [sp], #16".to_string());
+        // Return from the function.
+        lines.push("    ret".to_string());
+    }
+
+    fn generate_block(&mut self, block: &NodeBlock, lines: &mut Vec<String>) {
+        // The slots of the variables declared in the block are freed when the block ends, so they
+        // can be reused by the next block (or the next iteration of a loop).
+        let block_offset = self.current_offset;
+        for stmt in block.stmts.iter() {
+            self.generate_stmt(stmt, lines);
+        }
+        self.current_offset = block_offset;
+    }
+
+    fn generate_stmt(&mut self, stmt: &NodeStmt, lines: &mut Vec<String>) {
+        match stmt {
+            NodeStmt::Return(expr, _) if self.symbols.meta_type(expr).is_array() => {
+                // The frame is freed when the function returns, so an array is copied to the heap,
+                // and the caller gets its address (in x0, which the copy does not change).
+                let return_offset = self.current_offset;
+                self.generate_expr(expr, lines);
+                let offset = self.alloc_slot();
+                access_slot("str", "x9", offset, lines);
+                let size = value_size(self.symbols.meta_type(expr));
+                move_immediate("x0", size as i64, lines);
+                lines.push(format!("    bl {}", Os::symbol(runtime::ALLOC)));
+                lines.push("    mov x10, x0".to_string());
+                access_slot("ldr", "x9", offset, lines);
+                self.copy_value(size, lines);
+                self.current_offset = return_offset;
+                lines.push(format!("    b {}", self.epilogue_label));
+            }
+            NodeStmt::Return(expr, _) => {
+                self.generate_expr(expr, lines);
+                // Load the value in x9 (the first temporary register which will be used to store
+                // expression results) into x0 (the return register). Floats are already in d0,
+                // which is both where float results are kept and the float return register.
+                if !self.symbols.meta_type(expr).is_float() {
+                    lines.push("    mov x0, x9".to_string());
+                }
+                lines.push(format!("    b {}", self.epilogue_label));
+            }
+            NodeStmt::Assign(ident, a_type, expr, _, _) if a_type.meta.is_array() => {
+                // The array gets its space before its value is evaluated, and the value is copied
+                // into it.
+                let offset = self.alloc_region(value_size(&a_type.meta));
+                self.slots.insert(ident.span, offset);
+                self.generate_array_copy(expr, offset, lines);
+            }
+            NodeStmt::Assign(ident, _, expr, _, _) => {
+                self.generate_expr(expr, lines);
+                // Store the expression result onto the stack.
+                let offset = self.alloc_slot();
+                self.slots.insert(ident.span, offset);
+                let meta_type = self.symbols.variable_type(ident);
+                access_slot(
+                    store_instruction(meta_type),
+                    variable_register(meta_type),
+                    offset,
+                    lines,
+                );
+            }
+            NodeStmt::Reassign(ident, expr, _) if self.symbols.meta_type(expr).is_array() => {
+                let offset = self.slot(ident);
+                self.generate_array_copy(expr, offset, lines);
+            }
+            NodeStmt::Reassign(ident, expr, _) => {
+                self.generate_expr(expr, lines);
+                // Store the value into the slot the variable already has.
+                let offset = self.slot(ident);
+                let meta_type = self.symbols.variable_type(ident);
+                access_slot(
+                    store_instruction(meta_type),
+                    variable_register(meta_type),
+                    offset,
+                    lines,
+                );
+            }
+            NodeStmt::If(cond, then_block, else_block, _) => {
+                let else_label = self.new_label("if_else");
+                let end_label = self.new_label("if_end");
+                self.generate_expr(cond, lines);
+                // Any value other than 0 is true.
+                lines.push(format!("    cbz x9, {}", else_label));
+                self.generate_block(then_block, lines);
+                lines.push(format!("    b {}", end_label));
+                lines.push(format!("{}:", else_label));
+                if let Some(else_block) = else_block {
+                    self.generate_block(else_block, lines);
+                }
+                lines.push(format!("{}:", end_label));
+            }
+            NodeStmt::While(cond, body, _) => {
+                let head_label = self.new_label("while_head");
+                let exit_label = self.new_label("while_exit");
+                lines.push(format!("{}:", head_label));
+                self.generate_expr(cond, lines);
+                lines.push(format!("    cbz x9, {}", exit_label));
+                self.loop_labels
+                    .push((head_label.clone(), exit_label.clone()));
+                self.generate_block(body, lines);
+                self.loop_labels.pop();
+                lines.push(format!("    b {}", head_label));
+                lines.push(format!("{}:", exit_label));
+            }
+            // The frame is reserved once for the whole function, so jumping out of the blocks that
+            // variables are declared in does not need to free anything at runtime.
+            NodeStmt::Break(_) => {
+                let (_, exit_label) = self
+                    .loop_labels
+                    .last()
+                    .expect("Error, break outside a loop");
+                lines.push(format!("    b {}", exit_label));
+            }
+            NodeStmt::Continue(_) => {
+                let (head_label, _) = self
+                    .loop_labels
+                    .last()
+                    .expect("Error, continue outside a loop");
+                lines.push(format!("    b {}", head_label));
+            }
+            NodeStmt::IndexAssign(array, index, expr, _) => {
+                // The address of the array and the index are kept in slots while the value is
+                // evaluated. The index is only checked (and the address of the element computed)
+                // after that, since the value can change the length of a list, and the elements
+                // of a list move when it grows. The value is kept in x14 during the check.
+                let stmt_offset = self.current_offset;
+                self.generate_expr(array, lines);
+                let array_offset = self.alloc_slot();
+                access_slot("str", "x9", array_offset, lines);
+                let array_type = self.symbols.meta_type(array).clone();
+                let element_type = array_type
+                    .element_type()
+                    .expect("Error, indexed a value that is not an array")
+                    .clone();
+                self.generate_expr(index, lines);
+                let index_offset = self.alloc_slot();
+                access_slot("str", "x9", index_offset, lines);
+                self.generate_expr(expr, lines);
+                lines.push("    mov x14, x9".to_string());
+                access_slot("ldr", "x9", index_offset, lines);
+                self.generate_bounds_check(&array_type, array_offset, lines);
+                lines.push("    mov x9, x14".to_string());
+                load_elements("x10", &array_type, array_offset, lines);
+                access_slot("ldr", "x11", index_offset, lines);
+                let size = value_size(&element_type);
+                move_immediate("x12", size as i64, lines);
+                lines.push("    madd x10, x11, x12, x10".to_string());
+                if element_type.is_array() {
+                    self.copy_value(size, lines);
+                } else {
+                    lines.push(format!("    str {}, [x10]", value_register(&element_type)));
+                }
+                self.current_offset = stmt_offset;
+            }
+            NodeStmt::MemberAssign(object, field, expr, _) => {
+                // The object is kept in a slot while the value is evaluated.
+                let stmt_offset = self.current_offset;
+                self.generate_expr(object, lines);
+                let object_offset = self.alloc_slot();
+                access_slot("str", "x9", object_offset, lines);
+                self.generate_expr(expr, lines);
+                access_slot("ldr", "x10", object_offset, lines);
+                let offset = field_offset(&self.symbols, object, field);
+                field_address("x10", "x10", offset, lines);
+                let field_type = self.symbols.meta_type(expr);
+                if field_type.is_array() {
+                    self.copy_value(value_size(field_type), lines);
+                } else {
+                    lines.push(format!("    str {}, [x10]", value_register(field_type)));
+                }
+                self.current_offset = stmt_offset;
+            }
+            NodeStmt::Expr(expr, _) => self.generate_expr(expr, lines),
+        }
+    }
+
+    // Evaluates an array and copies its elements to the ones at an offset in the frame.
+    fn generate_array_copy(&mut self, expr: &NodeExpr, offset: i32, lines: &mut Vec<String>) {
+        let copy_offset = self.current_offset;
+        self.generate_expr(expr, lines);
+        slot_address("x10", offset, lines);
+        self.copy_value(value_size(self.symbols.meta_type(expr)), lines);
+        self.current_offset = copy_offset;
+    }
+
+    // Copies a value of size bytes (an array) from the address in x9 to the address in x10, 8
+    // bytes at a time. Both registers are left right after the copied bytes.
+    fn copy_value(&mut self, size: i32, lines: &mut Vec<String>) {
+        let loop_label = self.new_label("copy");
+        move_immediate("x11", size as i64, lines);
+        lines.push(format!("{}:", loop_label));
+        lines.push("    ldr x13, [x9], #8".to_string());
+        lines.push("    str x13, [x10], #8".to_string());
+        lines.push("    subs x11, x11, #8".to_string());
+        lines.push(format!("    b.ne {}", loop_label));
+    }
+
+    // Aborts the program when the index in x9 is not below the length of the array (or list) in
+    // the slot at array_offset. The index is compared as an unsigned number, so a negative index is
+    // out of bounds too.
+    fn generate_bounds_check(
+        &mut self,
+        array_type: &TypeMeta,
+        array_offset: i32,
+        lines: &mut Vec<String>,
+    ) {
+        let ok_label = self.new_label("index_ok");
+        match array_type {
+            TypeMeta::Array(_, len) => move_immediate("x11", *len as i64, lines),
+            // The length of a list is only known when the program runs.
+            _ => {
+                access_slot("ldr", "x11", array_offset, lines);
+                lines.push("    ldr x11, [x11]".to_string());
+            }
+        }
+        lines.push("    cmp x9, x11".to_string());
+        lines.push(format!("    b.lo {}", ok_label));
+        lines.push("    mov x0, x9".to_string());
+        lines.push("    mov x1, x11".to_string());
+        lines.push(format!("    bl {}", Os::symbol(runtime::OUT_OF_BOUNDS)));
+        lines.push(format!("{}:", ok_label));
+    }
+
+    fn generate_expr(&mut self, expr: &NodeExpr, lines: &mut Vec<String>) {
+        match expr {
+            NodeExpr::Literal(val, _) => {
+                // Load the literal into x9 (the first temporary register). Booleans are 1 (true)
+                // or 0 (false), and chars are their 8 bit code. Floats are loaded into d0.
+                match val {
+                    NodeLiteral::IntLit(val) => move_immediate("x9", *val, lines),
+                    NodeLiteral::BoolLit(val) => {
+                        lines.push(format!("    mov x9, #{}", *val as u8));
+                    }
+                    NodeLiteral::CharLit(val) => {
+                        lines.push(format!("    mov x9, #{}", val));
+                    }
+                    // FMOV can only encode a few floats as an immediate, so the bits of the float
+                    // are loaded as an integer and moved over.
+                    NodeLiteral::FloatLit(val) => {
+                        lines.push(format!("    ldr x9, ={}", val.to_bits()));
+                        lines.push("    fmov d0, x9".to_string());
+                    }
+                    // A string is a pointer to its length, which is followed by its bytes.
+                    NodeLiteral::Str(val) => {
+                        self.label_count += 1;
+                        let label = Os::data_label(&format!("string_{}", self.label_count));
+                        lines.extend(Os::load_address("x9", &label));
+                        self.strings.push((label, val.as_bytes().to_vec()));
+                    }
+                }
+            }
+            // The value of an array is the address of its elements.
+            NodeExpr::Ident(ident) if self.symbols.variable_type(ident).is_array() => {
+                slot_address("x9", self.slot(ident), lines);
+            }
+            NodeExpr::Ident(ident) => {
+                // Load the variable value into x9 (the first temporary register), or d0
+                let offset = self.slot(ident);
+                let meta_type = self.symbols.variable_type(ident);
+                access_slot(
+                    load_instruction(meta_type),
+                    variable_register(meta_type),
+                    offset,
+                    lines,
+                );
+            }
+            NodeExpr::Unary(op, expr, _) => {
+                self.generate_expr(expr, lines);
+                match op {
+                    UnaryOp::Neg if self.symbols.meta_type(expr).is_float() => {
+                        lines.push("    fneg d0, d0".to_string())
+                    }
+                    UnaryOp::Neg => lines.push("    neg x9, x9".to_string()),
+                    UnaryOp::BitNot => lines.push("    mvn x9, x9".to_string()),
+                    UnaryOp::Not => {
+                        lines.push("    cmp x9, #0".to_string());
+                        lines.push("    cset x9, eq".to_string());
+                    }
+                }
+            }
+            NodeExpr::Binary(op @ (BinaryOp::And | BinaryOp::Or), lhs, rhs, _) => {
+                // Logical operators short-circuit: the right hand side is only evaluated when the
+                // left hand side does not decide the result on its own. Both sides are turned
+                // into 0 or 1, so the result is always 0 or 1 too.
+                let end_label = self.new_label("logic_end");
+                self.generate_expr(lhs, lines);
+                lines.push("    cmp x9, #0".to_string());
+                lines.push("    cset x9, ne".to_string());
+                match op {
+                    BinaryOp::And => lines.push(format!("    cbz x9, {}", end_label)),
+                    _ => lines.push(format!("    cbnz x9, {}", end_label)),
+                }
+                self.generate_expr(rhs, lines);
+                lines.push("    cmp x9, #0".to_string());
+                lines.push("    cset x9, ne".to_string());
+                lines.push(format!("{}:", end_label));
+            }
+            NodeExpr::Binary(op, lhs, rhs, _) if self.symbols.meta_type(lhs).is_float() => {
+                // Same as below, with the operands in d0 and d1.
+                self.generate_expr(lhs, lines);
+                let offset = self.alloc_slot();
+                access_slot("str", "d0", offset, lines);
+                self.generate_expr(rhs, lines);
+                lines.push("    fmov d1, d0".to_string());
+                access_slot("ldr", "d0", offset, lines);
+                self.current_offset += 8;
+                match op {
+                    BinaryOp::Mul => lines.push("    fmul d0, d0, d1".to_string()),
+                    BinaryOp::Div => lines.push("    fdiv d0, d0, d1".to_string()),
+                    BinaryOp::Add => lines.push("    fadd d0, d0, d1".to_string()),
+                    BinaryOp::Sub => lines.push("    fsub d0, d0, d1".to_string()),
+                    // The result of a comparison is a bool, so it goes into x9.
+                    BinaryOp::Eq
+                    | BinaryOp::Ne
+                    | BinaryOp::Lt
+                    | BinaryOp::Le
+                    | BinaryOp::Gt
+                    | BinaryOp::Ge => {
+                        lines.push("    fcmp d0, d1".to_string());
+                        lines.push(format!("    cset x9, {}", float_condition_code(*op)));
+                    }
+                    BinaryOp::Mod | BinaryOp::And | BinaryOp::Or => {
+                        unreachable!("Error, {:?} cannot be applied to floats", op)
+                    }
+                }
+            }
+            NodeExpr::Binary(op, lhs, rhs, _)
+                if *self.symbols.meta_type(lhs) == TypeMeta::String =>
+            {
+                // Strings are concatenated and compared by the runtime, which gets the left hand
+                // side in x0 and the right hand side in x1.
+                self.generate_expr(lhs, lines);
+                let offset = self.alloc_slot();
+                access_slot("str", "x9", offset, lines);
+                self.generate_expr(rhs, lines);
+                lines.push("    mov x1, x9".to_string());
+                access_slot("ldr", "x0", offset, lines);
+                self.current_offset += 8;
+                let routine = match op {
+                    BinaryOp::Add => runtime::STRING_CONCAT,
+                    BinaryOp::Eq | BinaryOp::Ne => runtime::STRING_EQ,
+                    _ => unreachable!("Error, {:?} cannot be applied to strings", op),
+                };
+                lines.push(format!("    bl {}", Os::symbol(routine)));
+                lines.push("    mov x9, x0".to_string());
+                if *op == BinaryOp::Ne {
+                    lines.push("    eor x9, x9, #1".to_string());
+                }
+            }
+            NodeExpr::Binary(op, lhs, rhs, _) => {
+                // The left hand side is spilled to a temporary stack slot while the right hand
+                // side is evaluated, since evaluating it will overwrite x9.
+                self.generate_expr(lhs, lines);
+                let offset = self.alloc_slot();
+                access_slot("str", "x9", offset, lines);
+                self.generate_expr(rhs, lines);
+                lines.push("    mov x10, x9".to_string());
+                access_slot("ldr", "x9", offset, lines);
+                self.current_offset += 8;
+                match op {
+                    BinaryOp::Mul => lines.push("    mul x9, x9, x10".to_string()),
+                    // SDIV returns 0 when the divisor is zero, so that is checked first, and aborts
+                    // the program. The smallest int divided by -1 wraps around to itself, with a
+                    // remainder of 0.
+                    BinaryOp::Div | BinaryOp::Mod => {
+                        let ok_label = self.new_label("div_ok");
+                        lines.push(format!("    cbnz x10, {}", ok_label));
+                        lines.push(format!("    bl {}", Os::symbol(runtime::DIV_BY_ZERO)));
+                        lines.push(format!("{}:", ok_label));
+                        if *op == BinaryOp::Div {
+                            lines.push("    sdiv x9, x9, x10".to_string());
+                        } else {
+                            // There is no remainder instruction, so we compute
+                            // lhs - (lhs / rhs) * rhs
+                            lines.push("    sdiv x11, x9, x10".to_string());
+                            lines.push("    msub x9, x11, x10, x9".to_string());
+                        }
+                    }
+                    BinaryOp::Add => lines.push("    add x9, x9, x10".to_string()),
+                    BinaryOp::Sub => lines.push("    sub x9, x9, x10".to_string()),
+                    BinaryOp::Eq
+                    | BinaryOp::Ne
+                    | BinaryOp::Lt
+                    | BinaryOp::Le
+                    | BinaryOp::Gt
+                    | BinaryOp::Ge => {
+                        lines.push("    cmp x9, x10".to_string());
+                        lines.push(format!("    cset x9, {}", condition_code(*op)));
+                    }
+                    BinaryOp::And | BinaryOp::Or => {
+                        unreachable!("Error, logical operators are short-circuited")
+                    }
+                }
+            }
+            NodeExpr::Paren(expr, _) => self.generate_expr(expr, lines),
+            NodeExpr::Call(ident, args, _) => {
+                self.generate_call(ident, args, lines);
+            }
+            NodeExpr::MethodCall(object, method, args, _) => {
+                self.generate_method_call(object, method, args, lines);
+            }
+            // An array literal is evaluated into its own space in the frame, and its value is the
+            // address of its elements. That space is kept until the statement (or call) that uses
+            // the array is done with it.
+            NodeExpr::Array(elements, _) => {
+                let meta_type = self.symbols.meta_type(expr).clone();
+                let offset = self.alloc_region(value_size(&meta_type));
+                let mut element_offset = offset;
+                for element in elements.iter() {
+                    let element_start = self.current_offset;
+                    self.generate_expr(element, lines);
+                    let element_type = self.symbols.meta_type(element).clone();
+                    if element_type.is_array() {
+                        slot_address("x10", element_offset, lines);
+                        self.copy_value(value_size(&element_type), lines);
+                    } else {
+                        access_slot("str", value_register(&element_type), element_offset, lines);
+                    }
+                    element_offset += value_size(&element_type);
+                    self.current_offset = element_start;
+                }
+                slot_address("x9", offset, lines);
+            }
+            NodeExpr::ArrayRepeat(value, count, _) => {
+                // The value is evaluated once, and stored into every element by a loop (x10
+                // points to the next element, and x15 counts the elements that are left). The
+                // copy of an array moves x9, so its address is kept in x14.
+                let meta_type = self.symbols.meta_type(expr).clone();
+                let offset = self.alloc_region(value_size(&meta_type));
+                let value_start = self.current_offset;
+                self.generate_expr(value, lines);
+                let value_type = self.symbols.meta_type(value).clone();
+                let loop_label = self.new_label("array_repeat");
+                slot_address("x10", offset, lines);
+                move_immediate("x15", *count as i64, lines);
+                if value_type.is_array() {
+                    lines.push("    mov x14, x9".to_string());
+                }
+                lines.push(format!("{}:", loop_label));
+                if value_type.is_array() {
+                    lines.push("    mov x9, x14".to_string());
+                    self.copy_value(value_size(&value_type), lines);
+                } else {
+                    lines.push(format!(
+                        "    str {}, [x10], #8",
+                        value_register(&value_type)
+                    ));
+                }
+                lines.push("    subs x15, x15, #1".to_string());
+                lines.push(format!("    b.ne {}", loop_label));
+                self.current_offset = value_start;
+                slot_address("x9", offset, lines);
+            }
+            NodeExpr::Index(array, index, _) => {
+                // The address of the array is spilled while the index is evaluated. An element
+                // that is an array is used by its address, like any other array, so it keeps the
+                // array it is in.
+                let index_start = self.current_offset;
+                self.generate_expr(array, lines);
+                let array_end = self.current_offset;
+                let offset = self.alloc_slot();
+                access_slot("str", "x9", offset, lines);
+                let array_type = self.symbols.meta_type(array).clone();
+                let element_type = array_type
+                    .element_type()
+                    .expect("Error, indexed a value that is not an array")
+                    .clone();
+                self.generate_expr(index, lines);
+                self.generate_bounds_check(&array_type, offset, lines);
+                load_elements("x10", &array_type, offset, lines);
+                move_immediate("x11", value_size(&element_type) as i64, lines);
+                lines.push("    madd x9, x9, x11, x10".to_string());
+                if element_type.is_array() {
+                    self.current_offset = array_end;
+                } else {
+                    // Every element takes 8 bytes, even chars.
+                    let register = value_register(&element_type);
+                    lines.push(format!("    ldr {}, [x9]", register));
+                    self.current_offset = index_start;
+                }
+            }
+            NodeExpr::EmptyList(_) => {
+                lines.push(format!("    bl {}", Os::symbol(runtime::LIST_NEW)));
+                lines.push("    mov x9, x0".to_string());
+            }
+            NodeExpr::This(_) | NodeExpr::Super(_) => {
+                access_slot("ldr", "x9", self.this_offset, lines)
+            }
+            // The constructor of the base class initializes `this`, which is its own slot.
+            NodeExpr::SuperCall(keyword, args, _) => {
+                let call_offset = self.current_offset;
+                let function = self.symbols.function(keyword).clone();
+                self.generate_call_to(&function, args, vec![self.this_offset], None, lines);
+                self.current_offset = call_offset;
+            }
+            // A field that is an array is used by its address, like an element of an array. The
+            // variant of an enum is its value, which is a constant.
+            NodeExpr::Member(object, field, _) => {
+                if let Some(value) = self.symbols.variant(field) {
+                    move_immediate("x9", value as i64, lines);
+                    return;
+                }
+                self.generate_expr(object, lines);
+                let offset = field_offset(&self.symbols, object, field);
+                field_address("x9", "x9", offset, lines);
+                let field_type = self.symbols.meta_type(expr);
+                if !field_type.is_array() {
+                    lines.push(format!("    ldr {}, [x9]", value_register(field_type)));
+                }
+            }
+        }
+    }
+
+    fn generate_call(&mut self, ident: &NodeIdent, args: &[NodeExpr], lines: &mut Vec<String>) {
+        match self.symbols.function(ident).symbol.as_str() {
+            LIST_PUSH | LIST_POP | LIST_LEN => return self.generate_list_call(ident, args, lines),
+            _ => {}
+        }
+        let call_offset = self.current_offset;
+        let function = self.symbols.function(ident).clone();
+        let mut arg_offsets: Vec<i32> = Vec::new();
+        // A constructor is given a new object, which it initializes and returns.
+        if let FunctionKind::Constructor(class) = &function.kind {
+            move_immediate("x0", object_size(&self.symbols, class) as i64, lines);
+            lines.push(format!("    bl {}", Os::symbol(runtime::ALLOC)));
+            lines.extend(Os::load_address("x9", &Os::symbol(&vtable(class))));
+            lines.push("    str x9, [x0]".to_string());
+            let offset = self.alloc_slot();
+            arg_offsets.push(offset);
+            access_slot("str", "x0", offset, lines);
+        }
+        self.generate_call_to(&function, args, arg_offsets, None, lines);
+        self.current_offset = call_offset;
+    }
+
+    // Generates a call to a method, which is given the object it is called on as its first
+    // argument.
+    fn generate_method_call(
+        &mut self,
+        object: &NodeExpr,
+        method: &NodeIdent,
+        args: &[NodeExpr],
+        lines: &mut Vec<String>,
+    ) {
+        let call_offset = self.current_offset;
+        self.generate_expr(object, lines);
+        let offset = self.alloc_slot();
+        access_slot("str", "x9", offset, lines);
+        let function = self.symbols.function(method).clone();
+        // A method called through `super` is the one of the base class, any other is looked up in
+        // the vtable of the object.
+        let dispatch = match object {
+            NodeExpr::Super(_) => None,
+            _ => Some(vtable_offset(&self.symbols, object, &function)),
+        };
+        self.generate_call_to(&function, args, vec![offset], dispatch, lines);
+        self.current_offset = call_offset;
+    }
+
+    // Calls a function with the values in the slots at arg_offsets (the object of a constructor or
+    // a method), followed by the arguments. Every argument is evaluated (from left to right) into
+    // its own temporary stack slot, since evaluating one argument could overwrite the argument
+    // registers. A method is called through the vtable of its object when dispatch is the offset of
+    // the method in it.
+    fn generate_call_to(
+        &mut self,
+        function: &FunctionSymbol,
+        args: &[NodeExpr],
+        mut arg_offsets: Vec<i32>,
+        dispatch: Option<i32>,
+        lines: &mut Vec<String>,
+    ) {
+        let mut params = function.params.clone();
+        if let Some(class) = function.kind.class() {
+            params.insert(0, TypeMeta::Class(class.to_string()));
+        }
+        for arg in args.iter() {
+            self.generate_expr(arg, lines);
+            let offset = self.alloc_slot();
+            arg_offsets.push(offset);
+            access_slot(
+                "str",
+                value_register(self.symbols.meta_type(arg)),
+                offset,
+                lines,
+            );
+        }
+        let symbol = Os::symbol(&function.symbol);
+        let locations = arg_locations(
+            params.iter(),
+            ARG_REGISTERS.len(),
+            FLOAT_ARG_REGISTERS.len(),
+        );
+        let returns_float = function.r_type.is_float();
+        // The frame is already below sp, so only the arguments passed on the stack need extra
+        // room (keeping sp 16 byte aligned).
+        let stack_args = locations
+            .iter()
+            .filter(|location| matches!(location, ArgLocation::Stack(_)))
+            .count() as i32;
+        let stack_args_size = (stack_args * 8 + 15) / 16 * 16;
+        if stack_args_size > 0 {
+            lines.push(format!("    sub sp, sp, #{}", stack_args_size));
+        }
+        for (location, offset) in locations.into_iter().zip(arg_offsets) {
+            match location {
+                ArgLocation::IntRegister(idx) => {
+                    access_slot("ldr", ARG_REGISTERS[idx], offset, lines)
+                }
+                ArgLocation::FloatRegister(idx) => {
+                    access_slot("ldr", FLOAT_ARG_REGISTERS[idx], offset, lines)
+                }
+                // A float on the stack is just its 8 bytes, so it is copied like an integer.
+                ArgLocation::Stack(idx) => {
+                    access_slot("ldr", "x9", offset, lines);
+                    lines.push(format!("    str x9, [sp, #{}]", 8 * idx));
+                }
+            }
+        }
+        match dispatch {
+            // The object is the first argument, and x16 is not used to pass arguments.
+            Some(offset) => {
+                lines.push("    ldr x16, [x0]".to_string());
+                lines.push(format!("    ldr x16, [x16, #{}]", offset));
+                lines.push("    blr x16".to_string());
+            }
+            None => lines.push(format!("    bl {}", symbol)),
+        }
+        if stack_args_size > 0 {
+            lines.push(format!("    add sp, sp, #{}", stack_args_size));
+        }
+        // The return value is moved into x9, like the result of any other expression. A float
+        // return value is already in d0.
+        if !returns_float {
+            lines.push("    mov x9, x0".to_string());
+        }
+    }
+
+    // Generates a call to `push`, `pop` or `len` on a list. The runtime grows and shrinks the
+    // list, and gives back the address of the element that is pushed or popped, which is stored
+    // or loaded here since that depends on the type of the elements.
+    fn generate_list_call(
+        &mut self,
+        ident: &NodeIdent,
+        args: &[NodeExpr],
+        lines: &mut Vec<String>,
+    ) {
+        let call_offset = self.current_offset;
+        let function = self.symbols.function(ident).clone();
+        let element_type = function.params[0]
+            .element_type()
+            .expect("Error, list function called on a value that is not a list")
+            .clone();
+        let size = value_size(&element_type);
+        self.generate_expr(&args[0], lines);
+        match function.symbol.as_str() {
+            LIST_PUSH => {
+                let list_offset = self.alloc_slot();
+                access_slot("str", "x9", list_offset, lines);
+                self.generate_expr(&args[1], lines);
+                let value_offset = self.alloc_slot();
+                access_slot("str", value_register(&element_type), value_offset, lines);
+                access_slot("ldr", "x0", list_offset, lines);
+                move_immediate("x1", size as i64, lines);
+                lines.push(format!("    bl {}", Os::symbol(runtime::LIST_PUSH)));
+                lines.push("    mov x10, x0".to_string());
+                access_slot("ldr", "x9", value_offset, lines);
+                if element_type.is_array() {
+                    self.copy_value(size, lines);
+                } else {
+                    lines.push("    str x9, [x10]".to_string());
+                }
+            }
+            // An element that is an array is used by its address, which stays valid until something
+            // is pushed to the list again.
+            LIST_POP => {
+                lines.push("    mov x0, x9".to_string());
+                move_immediate("x1", size as i64, lines);
+                lines.push(format!("    bl {}", Os::symbol(runtime::LIST_POP)));
+                lines.push("    mov x9, x0".to_string());
+                if !element_type.is_array() {
+                    let register = value_register(&element_type);
+                    lines.push(format!("    ldr {}, [x9]", register));
+                }
+            }
+            _ => lines.push("    ldr x9, [x9]".to_string()),
+        }
+        self.current_offset = call_offset;
+    }
+}
+
+// Loads the address of the elements of the array (or list) in the slot at array_offset into a
+// register. The elements of a list are wherever it points to.
+fn load_elements(
+    register: &str,
+    array_type: &TypeMeta,
+    array_offset: i32,
+    lines: &mut Vec<String>,
+) {
+    access_slot("ldr", register, array_offset, lines);
+    if !array_type.is_array() {
+        lines.push(format!("    ldr {}, [{}, #16]", register, register));
+    }
+}
+
+// Loads (ldr) or stores (str) a register from or to a stack slot. Slots are addressed relative to
+// x29, but the offset of a load or store can only be between -256 and 255 when it is negative, so
+// the address of slots that are further away is computed into x12 first.
+fn access_slot(instruction: &str, register: &str, offset: i32, lines: &mut Vec<String>) {
+    if offset >= -256 {
+        lines.push(format!(
+            "    {} {}, [x29, #{}]",
+            instruction, register, offset
+        ));
+    } else {
+        slot_address("x12", offset, lines);
+        lines.push(format!("    {} {}, [x12]", instruction, register));
+    }
+}
+
+// Computes the address of a stack slot (or of the elements of an array) into a register. ADD and
+// SUB can only encode 12 bit immediates, so bigger offsets go through the register first.
+fn slot_address(register: &str, offset: i32, lines: &mut Vec<String>) {
+    if offset >= 0 {
+        lines.push(format!("    add {}, x29, #{}", register, offset));
+    } else if offset >= -0xfff {
+        lines.push(format!("    sub {}, x29, #{}", register, -offset));
+    } else {
+        move_immediate(register, -offset as i64, lines);
+        lines.push(format!("    sub {}, x29, {}", register, register));
+    }
+}
+
+// Computes the address of the field at an offset of the object in a register, into a register
+// (which can be the same one). Bigger offsets than ADD can encode go through x12.
+fn field_address(register: &str, object: &str, offset: i32, lines: &mut Vec<String>) {
+    if offset <= 0xfff {
+        lines.push(format!("    add {}, {}, #{}", register, object, offset));
+    } else {
+        move_immediate("x12", offset as i64, lines);
+        lines.push(format!("    add {}, {}, x12", register, object));
+    }
+}
+
+// Moves an integer into a register. MOV can only encode 16 bit immediates, bigger ones are loaded
+// from a literal pool that the assembler creates for us.
+fn move_immediate(register: &str, value: i64, lines: &mut Vec<String>) {
+    if (0..=0xffff).contains(&value) {
+        lines.push(format!("    mov {}, #{}", register, value));
+    } else {
+        lines.push(format!("    ldr {}, ={}", register, value));
+    }
+}
+
+// The register an expression result of a type is kept in.
+fn value_register(meta_type: &TypeMeta) -> &'static str {
+    if meta_type.is_float() {
+        "d0"
+    } else {
+        "x9"
+    }
+}
+
+// The register a variable of a type is loaded into or stored from, with the instructions that do
+// it. Chars are a single byte, which is accessed through w9 (and zero extended into x9 when it is
+// loaded).
+fn variable_register(meta_type: &TypeMeta) -> &'static str {
+    match meta_type {
+        TypeMeta::Primitive(PrimitiveType::Char) => "w9",
+        _ => value_register(meta_type),
+    }
+}
+
+fn load_instruction(meta_type: &TypeMeta) -> &'static str {
+    match meta_type {
+        TypeMeta::Primitive(PrimitiveType::Char) => "ldrb",
+        _ => "ldr",
+    }
+}
+
+fn store_instruction(meta_type: &TypeMeta) -> &'static str {
+    match meta_type {
+        TypeMeta::Primitive(PrimitiveType::Char) => "strb",
+        _ => "str",
+    }
+}
+
+// The condition code that is true when the comparison is (after a `cmp lhs, rhs`).
+fn condition_code(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Eq => "eq",
+        BinaryOp::Ne => "ne",
+        BinaryOp::Lt => "lt",
+        BinaryOp::Le => "le",
+        BinaryOp::Gt => "gt",
+        BinaryOp::Ge => "ge",
+        _ => unreachable!("Error, {:?} is not a comparison", op),
+    }
+}
+
+// The condition code that is true when the comparison is (after a `fcmp lhs, rhs`). When either
+// side is NaN the operands are unordered, and every comparison but `!=` has to be false, so `<` and
+// `<=` use the codes that are false for unordered operands (lt and le are true for them).
+fn float_condition_code(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Lt => "mi",
+        BinaryOp::Le => "ls",
+        _ => condition_code(op),
+    }
+}
@@ -0,0 +1,548 @@
+use crate::code_generator::aarch64::Aarch64Os;
+
+// The runtime is a handful of routines written in assembly, which are emitted along with every
+// program. They follow the same calling convention as the functions of the program, and only use
+// the registers a callee is allowed to overwrite.
+
+// The symbols of the runtime routines. Functions of the program are all prefixed with
+// `objection_`, so these cannot clash with them. The builtin functions are called like any other
+// function, through the symbol the semantic analysis gives them.
+pub const ALLOC: &str = "__objection_alloc";
+pub const STRING_CONCAT: &str = "__objection_string_concat";
+pub const STRING_EQ: &str = "__objection_string_eq";
+const LEN: &str = "__objection_len";
+const PRINT_INT: &str = "__objection_print_int";
+const PRINT_FLOAT: &str = "__objection_print_float";
+const PRINT_BOOL: &str = "__objection_print_bool";
+const PRINT_CHAR: &str = "__objection_print_char";
+const PRINT_STRING: &str = "__objection_print_string";
+pub const OUT_OF_BOUNDS: &str = "__objection_out_of_bounds";
+pub const DIV_BY_ZERO: &str = "__objection_div_by_zero";
+pub const LIST_NEW: &str = "__objection_list_new";
+pub const LIST_PUSH: &str = "__objection_list_push";
+pub const LIST_POP: &str = "__objection_list_pop";
+const WRITE_DIGITS: &str = "__objection_write_digits";
+
+// The file descriptors of the standard output and the standard error.
+const STDOUT: &str = "#1";
+const STDERR: &str = "#2";
+
+// The exit code of a program that is aborted because it indexed an array (or a list) out of its
+// bounds, or popped from an empty list.
+const OUT_OF_BOUNDS_EXIT_CODE: u32 = 101;
+
+// The exit code of a program that is aborted because it divided an int by zero (with `/` or `%`).
+const DIV_BY_ZERO_EXIT_CODE: u32 = 102;
+
+// The exit code of a program that is aborted because the OS did not give it more memory for the
+// heap.
+const OUT_OF_MEMORY_EXIT_CODE: u32 = 103;
+
+// The number of elements a list has room for once something is pushed to it. It doubles every
+// time the list runs out of room.
+const LIST_CAPACITY: u32 = 4;
+
+// The heap grows in chunks of (at least) this many bytes, which are requested with mmap.
+const HEAP_CHUNK: u32 = 0x100000;
+
+// The strings the runtime prints are added to the string literals of the program, as (label, bytes).
+pub fn generate_runtime<Os: Aarch64Os>(
+    lines: &mut Vec<String>,
+    strings: &mut Vec<(String, Vec<u8>)>,
+) {
+    generate_alloc::<Os>(lines);
+    generate_string_concat::<Os>(lines);
+    generate_string_eq::<Os>(lines);
+    generate_len::<Os>(lines);
+    generate_print_string::<Os>(lines);
+    generate_print_char::<Os>(lines);
+    generate_print_bool::<Os>(lines);
+    generate_write_digits::<Os>(lines);
+    generate_print_int::<Os>(lines);
+    generate_print_float::<Os>(lines);
+    generate_out_of_bounds::<Os>(lines);
+    generate_div_by_zero::<Os>(lines);
+    generate_list_new::<Os>(lines);
+    generate_list_push::<Os>(lines);
+    generate_list_pop::<Os>(lines);
+    for (name, value) in RUNTIME_STRINGS {
+        strings.push((
+            Os::data_label(&format!("runtime_{}", name)),
+            value.as_bytes().to_vec(),
+        ));
+    }
+    // The next free byte of the heap and the end of the current chunk, which start out empty.
+    lines.push(".data".to_string());
+    lines.push(".p2align 3".to_string());
+    lines.push(format!("{}:", Os::data_label("heap")));
+    lines.push("    .quad 0".to_string());
+    lines.push("    .quad 0".to_string());
+    lines.push(".text".to_string());
+}
+
+// alloc(size) returns a pointer to size bytes of memory, aligned to 8 bytes. It is a bump
+// allocator: memory is handed out from the current chunk, and never freed. When the chunk runs
+// out, a new one is mapped (and the rest of the old one is wasted). When the OS cannot map a new
+// chunk, the program is aborted.
+fn generate_alloc<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let grow_label = Os::local_label("alloc_grow");
+    let failed_label = Os::local_label("alloc_failed");
+    lines.push(format!("{}:", Os::symbol(ALLOC)));
+    lines.push("    add x0, x0, #7".to_string());
+    lines.push("    and x0, x0, #-8".to_string());
+    lines.extend(Os::load_address("x9", &Os::data_label("heap")));
+    lines.push("    ldp x10, x11, [x9]".to_string());
+    lines.push("    add x12, x10, x0".to_string());
+    lines.push("    cmp x12, x11".to_string());
+    lines.push(format!("    b.hi {}", grow_label));
+    lines.push("    str x12, [x9]".to_string());
+    lines.push("    mov x0, x10".to_string());
+    lines.push("    ret".to_string());
+    // The new chunk is big enough for the allocation. The size and the length of the chunk are
+    // kept on the stack during the syscall.
+    lines.push(format!("{}:", grow_label));
+    lines.push(format!("    mov x1, #{}", HEAP_CHUNK));
+    lines.push("    cmp x0, x1".to_string());
+    lines.push("    csel x1, x0, x1, hi".to_string());
+    lines.push("    stp x0, x1, [sp, #-16]!".to_string());
+    // mmap(NULL, length, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+    lines.push("    mov x0, #0".to_string());
+    lines.push("    mov x2, #3".to_string());
+    lines.push(format!("    mov x3, #{}", 0x2 | Os::MAP_ANONYMOUS));
+    lines.push("    mov x4, #-1".to_string());
+    lines.push("    mov x5, #0".to_string());
+    lines.push(format!(
+        "    mov {}, #{}",
+        Os::SYSCALL_REGISTER,
+        Os::MMAP_SYSCALL
+    ));
+    lines.push(format!("    svc {}", Os::SYSCALL_IMMEDIATE));
+    lines.extend(Os::branch_if_failed(&failed_label));
+    lines.push("    ldp x12, x13, [sp], #16".to_string());
+    lines.push("    add x10, x0, x12".to_string());
+    lines.push("    add x11, x0, x13".to_string());
+    lines.extend(Os::load_address("x9", &Os::data_label("heap")));
+    lines.push("    stp x10, x11, [x9]".to_string());
+    lines.push("    ret".to_string());
+    lines.push(format!("{}:", failed_label));
+    write_string::<Os>(lines, "out_of_memory", STDERR);
+    lines.push(format!("    mov x0, #{}", OUT_OF_MEMORY_EXIT_CODE));
+    lines.push(format!(
+        "    mov {}, #{}",
+        Os::SYSCALL_REGISTER,
+        Os::EXIT_SYSCALL
+    ));
+    lines.push(format!("    svc {}", Os::SYSCALL_IMMEDIATE));
+}
+
+// string_concat(lhs, rhs) returns a new string with the bytes of lhs followed by the bytes of rhs.
+fn generate_string_concat<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let lhs_label = Os::local_label("concat_lhs");
+    let rhs_label = Os::local_label("concat_rhs");
+    let end_label = Os::local_label("concat_end");
+    lines.push(format!("{}:", Os::symbol(STRING_CONCAT)));
+    lines.push("    stp x29, x30, [sp, #-32]!".to_string());
+    lines.push("    mov x29, sp".to_string());
+    lines.push("    stp x0, x1, [x29, #16]".to_string());
+    lines.push("    ldr x9, [x0]".to_string());
+    lines.push("    ldr x10, [x1]".to_string());
+    lines.push("    add x0, x9, x10".to_string());
+    lines.push("    add x0, x0, #8".to_string());
+    lines.push(format!("    bl {}", Os::symbol(ALLOC)));
+    // Both strings are reloaded, since alloc overwrites the registers. x1 and x2 point to their
+    // bytes, x9 and x10 hold their lengths, and x12 points to where the next byte is copied.
+    lines.push("    ldp x1, x2, [x29, #16]".to_string());
+    lines.push("    ldr x9, [x1], #8".to_string());
+    lines.push("    ldr x10, [x2], #8".to_string());
+    lines.push("    add x11, x9, x10".to_string());
+    lines.push("    str x11, [x0]".to_string());
+    lines.push("    add x12, x0, #8".to_string());
+    for (label, next_label, src, len) in [
+        (&lhs_label, &rhs_label, "x1", "x9"),
+        (&rhs_label, &end_label, "x2", "x10"),
+    ] {
+        lines.push(format!("{}:", label));
+        lines.push(format!("    cbz {}, {}", len, next_label));
+        lines.push(format!("    ldrb w13, [{}], #1", src));
+        lines.push("    strb w13, [x12], #1".to_string());
+        lines.push(format!("    sub {}, {}, #1", len, len));
+        lines.push(format!("    b {}", label));
+    }
+    lines.push(format!("{}:", end_label));
+    lines.push("    ldp x29, x30, [sp], #32".to_string());
+    lines.push("    ret".to_string());
+}
+
+// string_eq(lhs, rhs) returns 1 if both strings have the same bytes, and 0 otherwise.
+fn generate_string_eq<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let loop_label = Os::local_label("string_eq_loop");
+    let true_label = Os::local_label("string_eq_true");
+    let false_label = Os::local_label("string_eq_false");
+    lines.push(format!("{}:", Os::symbol(STRING_EQ)));
+    lines.push("    ldr x9, [x0], #8".to_string());
+    lines.push("    ldr x10, [x1], #8".to_string());
+    lines.push("    cmp x9, x10".to_string());
+    lines.push(format!("    b.ne {}", false_label));
+    lines.push(format!("{}:", loop_label));
+    lines.push(format!("    cbz x9, {}", true_label));
+    lines.push("    ldrb w10, [x0], #1".to_string());
+    lines.push("    ldrb w11, [x1], #1".to_string());
+    lines.push("    cmp w10, w11".to_string());
+    lines.push(format!("    b.ne {}", false_label));
+    lines.push("    sub x9, x9, #1".to_string());
+    lines.push(format!("    b {}", loop_label));
+    lines.push(format!("{}:", true_label));
+    lines.push("    mov x0, #1".to_string());
+    lines.push("    ret".to_string());
+    lines.push(format!("{}:", false_label));
+    lines.push("    mov x0, #0".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin len(s: string): int, which returns the number of bytes in a string.
+fn generate_len<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", Os::symbol(LEN)));
+    lines.push("    ldr x0, [x0]".to_string());
+    lines.push("    ret".to_string());
+}
+
+// Makes the write syscall, which writes x2 bytes from x1 to a file descriptor (an immediate or a
+// register).
+fn write<Os: Aarch64Os>(lines: &mut Vec<String>, fd: &str) {
+    lines.push(format!("    mov x0, {}", fd));
+    lines.push(format!(
+        "    mov {}, #{}",
+        Os::SYSCALL_REGISTER,
+        Os::WRITE_SYSCALL
+    ));
+    lines.push(format!("    svc {}", Os::SYSCALL_IMMEDIATE));
+}
+
+// The builtin print(s: string).
+fn generate_print_string<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", Os::symbol(PRINT_STRING)));
+    lines.push("    ldr x2, [x0]".to_string());
+    lines.push("    add x1, x0, #8".to_string());
+    write::<Os>(lines, STDOUT);
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(c: char). The char is written from the stack.
+fn generate_print_char<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", Os::symbol(PRINT_CHAR)));
+    lines.push("    strb w0, [sp, #-16]!".to_string());
+    lines.push("    mov x1, sp".to_string());
+    lines.push("    mov x2, #1".to_string());
+    write::<Os>(lines, STDOUT);
+    lines.push("    add sp, sp, #16".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(b: bool), which prints `true` or `false`.
+fn generate_print_bool<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let false_label = Os::local_label("print_bool_false");
+    lines.push(format!("{}:", Os::symbol(PRINT_BOOL)));
+    lines.push(format!("    cbz x0, {}", false_label));
+    lines.extend(Os::load_address("x0", &Os::data_label("runtime_true")));
+    lines.push(format!("    b {}", Os::symbol(PRINT_STRING)));
+    lines.push(format!("{}:", false_label));
+    lines.extend(Os::load_address("x0", &Os::data_label("runtime_false")));
+    lines.push(format!("    b {}", Os::symbol(PRINT_STRING)));
+}
+
+// write_digits(value, min_digits, fd) writes an unsigned number in decimal to a file descriptor,
+// with leading zeros if it has less than min_digits digits. The digits are put in a buffer on the
+// stack from the last one to the first one, and then written all at once. The file descriptor is
+// kept in x8, since x2 is needed for the write.
+fn generate_write_digits<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let loop_label = Os::local_label("write_digits_loop");
+    lines.push(format!("{}:", Os::symbol(WRITE_DIGITS)));
+    lines.push("    mov x8, x2".to_string());
+    lines.push("    sub sp, sp, #32".to_string());
+    lines.push("    add x3, sp, #32".to_string());
+    lines.push("    mov x4, x3".to_string());
+    lines.push("    mov x5, #10".to_string());
+    lines.push(format!("{}:", loop_label));
+    lines.push("    udiv x6, x0, x5".to_string());
+    lines.push("    msub x7, x6, x5, x0".to_string());
+    lines.push("    add x7, x7, #48".to_string());
+    lines.push("    strb w7, [x4, #-1]!".to_string());
+    lines.push("    mov x0, x6".to_string());
+    lines.push("    sub x1, x1, #1".to_string());
+    lines.push(format!("    cbnz x0, {}", loop_label));
+    lines.push("    cmp x1, #0".to_string());
+    lines.push(format!("    b.gt {}", loop_label));
+    lines.push("    sub x2, x3, x4".to_string());
+    lines.push("    mov x1, x4".to_string());
+    write::<Os>(lines, "x8");
+    lines.push("    add sp, sp, #32".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(n: int). Negating the smallest int overflows back to itself, which is still
+// the right number once it is treated as unsigned.
+fn generate_print_int<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let positive_label = Os::local_label("print_int_positive");
+    lines.push(format!("{}:", Os::symbol(PRINT_INT)));
+    lines.push("    stp x29, x30, [sp, #-32]!".to_string());
+    lines.push("    mov x29, sp".to_string());
+    lines.push(format!("    tbz x0, #63, {}", positive_label));
+    lines.push("    neg x0, x0".to_string());
+    lines.push("    str x0, [x29, #16]".to_string());
+    lines.push("    mov x0, #45".to_string());
+    lines.push(format!("    bl {}", Os::symbol(PRINT_CHAR)));
+    lines.push("    ldr x0, [x29, #16]".to_string());
+    lines.push(format!("{}:", positive_label));
+    lines.push("    mov x1, #1".to_string());
+    lines.push(format!("    mov x2, {}", STDOUT));
+    lines.push(format!("    bl {}", Os::symbol(WRITE_DIGITS)));
+    lines.push("    ldp x29, x30, [sp], #32".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(x: float), which prints the float with 6 decimals (like `%f` in C), or `nan`
+// or `inf`. Floats that are too big for an int are divided by 10 until they fit, and the digits
+// that were divided away are printed as zeros, so they are only precise up to their first 18
+// digits.
+fn generate_print_float<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let positive_label = Os::local_label("print_float_positive");
+    let scale_label = Os::local_label("print_float_scale");
+    let scaled_label = Os::local_label("print_float_scaled");
+    let rounded_label = Os::local_label("print_float_rounded");
+    let zeros_label = Os::local_label("print_float_zeros");
+    let point_label = Os::local_label("print_float_point");
+    let nan_label = Os::local_label("print_float_nan");
+    let inf_label = Os::local_label("print_float_inf");
+    let end_label = Os::local_label("print_float_end");
+    lines.push(format!("{}:", Os::symbol(PRINT_FLOAT)));
+    lines.push("    stp x29, x30, [sp, #-32]!".to_string());
+    lines.push("    mov x29, sp".to_string());
+    // NaN is the only float that is unordered with itself.
+    lines.push("    fcmp d0, d0".to_string());
+    lines.push(format!("    b.vs {}", nan_label));
+    lines.push("    fmov x9, d0".to_string());
+    lines.push(format!("    tbz x9, #63, {}", positive_label));
+    lines.push("    fneg d0, d0".to_string());
+    lines.push("    str d0, [x29, #16]".to_string());
+    lines.push("    mov x0, #45".to_string());
+    lines.push(format!("    bl {}", Os::symbol(PRINT_CHAR)));
+    lines.push("    ldr d0, [x29, #16]".to_string());
+    lines.push(format!("{}:", positive_label));
+    lines.push(format!("    ldr x9, ={}", f64::INFINITY.to_bits()));
+    lines.push("    fmov d1, x9".to_string());
+    lines.push("    fcmp d0, d1".to_string());
+    lines.push(format!("    b.eq {}", inf_label));
+    // x11 counts the digits that were divided away.
+    lines.push(format!("    ldr x9, ={}", 1e18f64.to_bits()));
+    lines.push("    fmov d1, x9".to_string());
+    lines.push("    fmov d2, #10.0".to_string());
+    lines.push("    mov x11, #0".to_string());
+    lines.push(format!("{}:", scale_label));
+    lines.push("    fcmp d0, d1".to_string());
+    lines.push(format!("    b.lt {}", scaled_label));
+    lines.push("    fdiv d0, d0, d2".to_string());
+    lines.push("    add x11, x11, #1".to_string());
+    lines.push(format!("    b {}", scale_label));
+    // The integer part goes in x0 and the decimals (rounded to 6 digits) in x1. Rounding can
+    // carry into the integer part (0.9999999 is 1.000000).
+    lines.push(format!("{}:", scaled_label));
+    lines.push("    fcvtzu x0, d0".to_string());
+    lines.push("    ucvtf d1, x0".to_string());
+    lines.push("    fsub d1, d0, d1".to_string());
+    lines.push(format!("    ldr x9, ={}", 1e6f64.to_bits()));
+    lines.push("    fmov d2, x9".to_string());
+    lines.push("    fmul d1, d1, d2".to_string());
+    lines.push("    fcvtau x1, d1".to_string());
+    lines.push("    ldr x9, =1000000".to_string());
+    lines.push("    cmp x1, x9".to_string());
+    lines.push(format!("    b.lo {}", rounded_label));
+    lines.push("    mov x1, #0".to_string());
+    lines.push("    add x0, x0, #1".to_string());
+    lines.push(format!("{}:", rounded_label));
+    lines.push("    stp x1, x11, [x29, #16]".to_string());
+    lines.push("    mov x1, #1".to_string());
+    lines.push(format!("    mov x2, {}", STDOUT));
+    lines.push(format!("    bl {}", Os::symbol(WRITE_DIGITS)));
+    lines.push(format!("{}:", zeros_label));
+    lines.push("    ldr x11, [x29, #24]".to_string());
+    lines.push(format!("    cbz x11, {}", point_label));
+    lines.push("    sub x11, x11, #1".to_string());
+    lines.push("    str x11, [x29, #24]".to_string());
+    lines.push("    mov x0, #48".to_string());
+    lines.push(format!("    bl {}", Os::symbol(PRINT_CHAR)));
+    lines.push(format!("    b {}", zeros_label));
+    lines.push(format!("{}:", point_label));
+    lines.push("    mov x0, #46".to_string());
+    lines.push(format!("    bl {}", Os::symbol(PRINT_CHAR)));
+    lines.push("    ldr x0, [x29, #16]".to_string());
+    lines.push("    mov x1, #6".to_string());
+    lines.push(format!("    mov x2, {}", STDOUT));
+    lines.push(format!("    bl {}", Os::symbol(WRITE_DIGITS)));
+    lines.push(format!("    b {}", end_label));
+    lines.push(format!("{}:", nan_label));
+    lines.extend(Os::load_address("x0", &Os::data_label("runtime_nan")));
+    lines.push(format!("    bl {}", Os::symbol(PRINT_STRING)));
+    lines.push(format!("    b {}", end_label));
+    lines.push(format!("{}:", inf_label));
+    lines.extend(Os::load_address("x0", &Os::data_label("runtime_inf")));
+    lines.push(format!("    bl {}", Os::symbol(PRINT_STRING)));
+    lines.push(format!("{}:", end_label));
+    lines.push("    ldp x29, x30, [sp], #32".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The strings the runtime writes, by the name of their label.
+const RUNTIME_STRINGS: [(&str, &str); 11] = [
+    ("true", "true"),
+    ("false", "false"),
+    ("nan", "nan"),
+    ("inf", "inf"),
+    ("minus", "-"),
+    ("out_of_bounds", "error: Index "),
+    ("out_of_bounds_len", " is out of bounds for length "),
+    ("out_of_bounds_end", ".\n"),
+    ("empty_pop", "error: Cannot pop from an empty list.\n"),
+    ("div_by_zero", "error: Division by zero.\n"),
+    ("out_of_memory", "error: Out of memory.\n"),
+];
+
+// Writes one of the strings of the runtime to a file descriptor.
+fn write_string<Os: Aarch64Os>(lines: &mut Vec<String>, name: &str, fd: &str) {
+    lines.extend(Os::load_address(
+        "x1",
+        &Os::data_label(&format!("runtime_{}", name)),
+    ));
+    lines.push("    ldr x2, [x1], #8".to_string());
+    write::<Os>(lines, fd);
+}
+
+// out_of_bounds(index, len) aborts the program when it indexes an array out of its bounds, after
+// writing the index and the length of the array to the standard error. It never returns, so it
+// keeps them in callee-saved registers without restoring them.
+fn generate_out_of_bounds<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let positive_label = Os::local_label("out_of_bounds_positive");
+    lines.push(format!("{}:", Os::symbol(OUT_OF_BOUNDS)));
+    lines.push("    mov x19, x0".to_string());
+    lines.push("    mov x20, x1".to_string());
+    write_string::<Os>(lines, "out_of_bounds", STDERR);
+    lines.push(format!("    tbz x19, #63, {}", positive_label));
+    write_string::<Os>(lines, "minus", STDERR);
+    lines.push("    neg x19, x19".to_string());
+    lines.push(format!("{}:", positive_label));
+    lines.push("    mov x0, x19".to_string());
+    lines.push("    mov x1, #1".to_string());
+    lines.push(format!("    mov x2, {}", STDERR));
+    lines.push(format!("    bl {}", Os::symbol(WRITE_DIGITS)));
+    write_string::<Os>(lines, "out_of_bounds_len", STDERR);
+    lines.push("    mov x0, x20".to_string());
+    lines.push("    mov x1, #1".to_string());
+    lines.push(format!("    mov x2, {}", STDERR));
+    lines.push(format!("    bl {}", Os::symbol(WRITE_DIGITS)));
+    write_string::<Os>(lines, "out_of_bounds_end", STDERR);
+    lines.push(format!("    mov x0, #{}", OUT_OF_BOUNDS_EXIT_CODE));
+    lines.push(format!(
+        "    mov {}, #{}",
+        Os::SYSCALL_REGISTER,
+        Os::EXIT_SYSCALL
+    ));
+    lines.push(format!("    svc {}", Os::SYSCALL_IMMEDIATE));
+}
+
+// div_by_zero() aborts the program when it divides an int by zero.
+fn generate_div_by_zero<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", Os::symbol(DIV_BY_ZERO)));
+    write_string::<Os>(lines, "div_by_zero", STDERR);
+    lines.push(format!("    mov x0, #{}", DIV_BY_ZERO_EXIT_CODE));
+    lines.push(format!(
+        "    mov {}, #{}",
+        Os::SYSCALL_REGISTER,
+        Os::EXIT_SYSCALL
+    ));
+    lines.push(format!("    svc {}", Os::SYSCALL_IMMEDIATE));
+}
+
+// list_new() returns a new list, which has no elements and no room for any. A list is its length,
+// its capacity and the address of its elements (8 bytes each).
+fn generate_list_new<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", Os::symbol(LIST_NEW)));
+    lines.push("    stp x29, x30, [sp, #-16]!".to_string());
+    lines.push("    mov x29, sp".to_string());
+    lines.push("    mov x0, #24".to_string());
+    lines.push(format!("    bl {}", Os::symbol(ALLOC)));
+    lines.push("    stp xzr, xzr, [x0]".to_string());
+    lines.push("    str xzr, [x0, #16]".to_string());
+    lines.push("    ldp x29, x30, [sp], #16".to_string());
+    lines.push("    ret".to_string());
+}
+
+// list_push(list, size) adds an element of size bytes at the end of a list, and returns its
+// address, so the caller can store the value. When the list runs out of room, its elements are
+// copied to new memory with twice the room (the old memory is not freed).
+fn generate_list_push<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let room_label = Os::local_label("list_push_room");
+    let copy_label = Os::local_label("list_push_copy");
+    let copied_label = Os::local_label("list_push_copied");
+    lines.push(format!("{}:", Os::symbol(LIST_PUSH)));
+    lines.push("    ldp x9, x10, [x0]".to_string());
+    lines.push("    cmp x9, x10".to_string());
+    lines.push(format!("    b.lo {}", room_label));
+    lines.push("    stp x29, x30, [sp, #-32]!".to_string());
+    lines.push("    mov x29, sp".to_string());
+    lines.push("    stp x0, x1, [x29, #16]".to_string());
+    lines.push("    lsl x10, x10, #1".to_string());
+    lines.push(format!("    mov x11, #{}", LIST_CAPACITY));
+    lines.push("    cmp x10, x11".to_string());
+    lines.push("    csel x10, x10, x11, hs".to_string());
+    lines.push("    str x10, [x0, #8]".to_string());
+    lines.push("    mul x0, x10, x1".to_string());
+    lines.push(format!("    bl {}", Os::symbol(ALLOC)));
+    // The list and the size are reloaded, since alloc overwrites the registers. x10 counts the
+    // bytes that are left to copy from x11 to x12, 8 at a time.
+    lines.push("    ldp x9, x1, [x29, #16]".to_string());
+    lines.push("    ldr x10, [x9]".to_string());
+    lines.push("    mul x10, x10, x1".to_string());
+    lines.push("    ldr x11, [x9, #16]".to_string());
+    lines.push("    mov x12, x0".to_string());
+    lines.push(format!("{}:", copy_label));
+    lines.push(format!("    cbz x10, {}", copied_label));
+    lines.push("    ldr x13, [x11], #8".to_string());
+    lines.push("    str x13, [x12], #8".to_string());
+    lines.push("    sub x10, x10, #8".to_string());
+    lines.push(format!("    b {}", copy_label));
+    lines.push(format!("{}:", copied_label));
+    lines.push("    str x0, [x9, #16]".to_string());
+    lines.push("    mov x0, x9".to_string());
+    lines.push("    ldp x29, x30, [sp], #32".to_string());
+    lines.push(format!("{}:", room_label));
+    lines.push("    ldr x9, [x0]".to_string());
+    lines.push("    ldr x10, [x0, #16]".to_string());
+    lines.push("    madd x11, x9, x1, x10".to_string());
+    lines.push("    add x9, x9, #1".to_string());
+    lines.push("    str x9, [x0]".to_string());
+    lines.push("    mov x0, x11".to_string());
+    lines.push("    ret".to_string());
+}
+
+// list_pop(list, size) removes the last element (of size bytes) of a list, and returns its
+// address, so the caller can load the value. Popping from an empty list aborts the program, like
+// indexing it out of its bounds.
+fn generate_list_pop<Os: Aarch64Os>(lines: &mut Vec<String>) {
+    let empty_label = Os::local_label("list_pop_empty");
+    lines.push(format!("{}:", Os::symbol(LIST_POP)));
+    lines.push("    ldr x9, [x0]".to_string());
+    lines.push(format!("    cbz x9, {}", empty_label));
+    lines.push("    sub x9, x9, #1".to_string());
+    lines.push("    str x9, [x0]".to_string());
+    lines.push("    ldr x10, [x0, #16]".to_string());
+    lines.push("    madd x0, x9, x1, x10".to_string());
+    lines.push("    ret".to_string());
+    lines.push(format!("{}:", empty_label));
+    write_string::<Os>(lines, "empty_pop", STDERR);
+    lines.push(format!("    mov x0, #{}", OUT_OF_BOUNDS_EXIT_CODE));
+    lines.push(format!(
+        "    mov {}, #{}",
+        Os::SYSCALL_REGISTER,
+        Os::EXIT_SYSCALL
+    ));
+    lines.push(format!("    svc {}", Os::SYSCALL_IMMEDIATE));
+}
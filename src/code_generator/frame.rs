@@ -0,0 +1,50 @@
+use super::super::parser::ast::*;
+
+// The size of a stack slot. Every value but an array fits in 8 bytes (chars only use the first
+// one, and strings, lists and objects are pointers).
+const SLOT_SIZE: i32 = 8;
+
+// Where an argument is passed. Floats are passed in the floating point registers and every other
+// value in the integer registers, each kind taking the next free register of its own. Once a kind
+// runs out of registers, its arguments go on the stack, in order, 8 bytes each.
+#[derive(Clone, Copy)]
+pub enum ArgLocation {
+    IntRegister(usize),
+    FloatRegister(usize),
+    Stack(usize),
+}
+
+// Assigns a location to every argument of a call (or parameter of a function), given how many
+// registers of each kind the calling convention uses.
+pub fn arg_locations<'a>(
+    types: impl Iterator<Item = &'a TypeMeta>,
+    int_registers: usize,
+    float_registers: usize,
+) -> Vec<ArgLocation> {
+    let (mut ints, mut floats, mut stack) = (0, 0, 0);
+    types
+        .map(|meta_type| match meta_type {
+            meta_type if meta_type.is_float() && floats < float_registers => {
+                floats += 1;
+                ArgLocation::FloatRegister(floats - 1)
+            }
+            meta_type if !meta_type.is_float() && ints < int_registers => {
+                ints += 1;
+                ArgLocation::IntRegister(ints - 1)
+            }
+            _ => {
+                stack += 1;
+                ArgLocation::Stack(stack - 1)
+            }
+        })
+        .collect()
+}
+
+// The number of bytes a value of a type takes in the frame (or in a list, or an object). Arrays are stored in
+// the frame, one element after the other, and every other value takes a slot.
+pub fn value_size(meta_type: &TypeMeta) -> i32 {
+    match meta_type {
+        TypeMeta::Array(element, len) => value_size(element) * *len as i32,
+        _ => SLOT_SIZE,
+    }
+}
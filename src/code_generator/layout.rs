@@ -0,0 +1,54 @@
+use super::super::parser::ast::*;
+use super::super::semantic::semantic::{FunctionSymbol, SymbolTable};
+use super::frame::value_size;
+
+// Objects are stored on the heap, starting with the address of the vtable of their class, followed
+// by their fields one after the other, in the order they are declared (the ones of the base class
+// first, so an object starts like an object of its base class). A field takes as many bytes as its
+// value does in the frame, so the elements of an array field are stored in the object itself.
+
+// The bytes the address of the vtable takes at the start of an object.
+const VTABLE_SIZE: i32 = 8;
+
+// The number of bytes an object of a class takes.
+pub fn object_size(symbols: &SymbolTable, class: &str) -> i32 {
+    VTABLE_SIZE
+        + symbols
+            .class(class)
+            .fields
+            .iter()
+            .map(|field| value_size(&field.meta_type))
+            .sum::<i32>()
+}
+
+// The offset of a field from the start of the object (the value of an object expression).
+pub fn field_offset(symbols: &SymbolTable, object: &NodeExpr, field: &NodeIdent) -> i32 {
+    let TypeMeta::Class(class) = symbols.meta_type(object) else {
+        unreachable!("Error, accessed a field of a value that is not an object")
+    };
+    let fields = &symbols.class(class).fields;
+    let idx = fields
+        .iter()
+        .position(|other| other.name == field.name)
+        .expect("Error, field was not declared");
+    VTABLE_SIZE
+        + fields[..idx]
+            .iter()
+            .map(|field| value_size(&field.meta_type))
+            .sum::<i32>()
+}
+
+// The offset of a method from the start of the vtable of an object (the value of an object
+// expression). It is the same for the class of the object and every class that extends it.
+pub fn vtable_offset(symbols: &SymbolTable, object: &NodeExpr, method: &FunctionSymbol) -> i32 {
+    let TypeMeta::Class(class) = symbols.meta_type(object) else {
+        unreachable!("Error, called a method of a value that is not an object")
+    };
+    let idx = symbols
+        .class(class)
+        .vtable
+        .iter()
+        .position(|other| other.symbol == method.symbol)
+        .expect("Error, method is not in the vtable");
+    8 * idx as i32
+}
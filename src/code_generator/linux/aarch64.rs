@@ -0,0 +1,44 @@
+use crate::code_generator::aarch64::Aarch64;
+use crate::code_generator::aarch64::Aarch64Os;
+
+pub struct Linux;
+
+impl Aarch64Os for Linux {
+    const ENTRY_SYMBOL: &'static str = "_start";
+    // Linux passes the syscall number in x8 and traps with `svc #0`.
+    const SYSCALL_REGISTER: &'static str = "x8";
+    const SYSCALL_IMMEDIATE: &'static str = "#0";
+    const EXIT_SYSCALL: u32 = 93;
+    const WRITE_SYSCALL: u32 = 64;
+    const MMAP_SYSCALL: u32 = 222;
+    const MAP_ANONYMOUS: u32 = 0x20;
+    const RODATA_SECTION: &'static str = ".section .rodata";
+
+    // ELF symbols are used as they are.
+    fn symbol(name: &str) -> String {
+        name.to_string()
+    }
+    // Labels that start with .L are not put in the symbol table.
+    fn local_label(name: &str) -> String {
+        format!(".L{}", name)
+    }
+    fn data_label(name: &str) -> String {
+        format!(".L{}", name)
+    }
+    // The page of the label, then its offset in the page (its low 12 bits).
+    fn load_address(register: &str, label: &str) -> Vec<String> {
+        vec![
+            format!("    adrp {}, {}", register, label),
+            format!("    add {}, {}, :lo12:{}", register, register, label),
+        ]
+    }
+    // A failed syscall returns -errno, which is between -4095 and -1.
+    fn branch_if_failed(label: &str) -> Vec<String> {
+        vec![
+            "    cmn x0, #4096".to_string(),
+            format!("    b.hs {}", label),
+        ]
+    }
+}
+
+pub type LinuxAarch64 = Aarch64<Linux>;
@@ -0,0 +1,1036 @@
+use std::collections::HashMap;
+
+use super::super::super::parser::ast::*;
+use super::super::super::semantic::mangle::{mangle, vtable};
+use super::super::super::semantic::semantic::{
+    FunctionKind, FunctionSymbol, SymbolTable, LIST_LEN, LIST_POP, LIST_PUSH,
+};
+
+use crate::code_generator::frame::{arg_locations, value_size, ArgLocation};
+use crate::code_generator::layout::{field_offset, object_size, vtable_offset};
+use crate::code_generator::CodeGenerator;
+
+mod runtime;
+
+// The registers used to pass the first arguments of a function (System V AMD64 ABI), for integer
+// and for floating point arguments. The rest of the arguments are pushed on the stack.
+const ARG_REGISTERS: [&str; 6] = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
+const FLOAT_ARG_REGISTERS: [&str; 8] = [
+    "xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7",
+];
+
+pub struct LinuxX86_64 {
+    symbols: SymbolTable,
+    // The stack slot of every variable of the function being generated, by the span of the name
+    // of the variable in its declaration.
+    slots: HashMap<Span, i32>,
+    current_offset: i32,
+    // The deepest the slots of the function being generated got, which is what its frame reserves.
+    frame_size: i32,
+    // The slot of `this`, when the function being generated is a constructor.
+    this_offset: i32,
+    // The label of the epilogue of the function being generated, which every return jumps to.
+    epilogue_label: String,
+    label_count: usize,
+    // The head and exit labels of the loops we are in (the innermost loop is the last one). These
+    // are where `continue` and `break` jump to.
+    loop_labels: Vec<(String, String)>,
+    // The label and the bytes of every string literal of the program, which are emitted as
+    // read-only data after the code.
+    strings: Vec<(String, Vec<u8>)>,
+}
+
+impl CodeGenerator for LinuxX86_64 {
+    fn generate(&mut self, prog: &NodeProg, symbols: SymbolTable) -> String {
+        let mut lines = vec![
+            ".intel_syntax noprefix".to_string(),
+            ".global _start".to_string(),
+            "_start:".to_string(),
+            format!("    call {}", mangle(None, "main", &[])),
+            // The exit syscall (60) takes the exit code in rdi.
+            "    mov rdi, rax".to_string(),
+            "    mov rax, 60".to_string(),
+            "    syscall".to_string(),
+        ];
+        self.symbols = symbols;
+        for class in prog.classes.iter() {
+            for method in class.constructors.iter().chain(class.methods.iter()) {
+                self.generate_function(&method.func, &mut lines);
+            }
+        }
+        for function in prog.functions.iter() {
+            self.generate_function(function, &mut lines);
+        }
+        self.generate_vtables(prog, &mut lines);
+        runtime::generate_runtime(&mut lines, &mut self.strings);
+        self.generate_strings(&mut lines);
+        lines.join("\n")
+    }
+}
+
+impl LinuxX86_64 {
+    pub fn new() -> Self {
+        LinuxX86_64 {
+            symbols: SymbolTable::default(),
+            slots: HashMap::new(),
+            current_offset: 0,
+            frame_size: 0,
+            this_offset: 0,
+            epilogue_label: String::new(),
+            label_count: 0,
+            loop_labels: Vec::new(),
+            strings: Vec::new(),
+        }
+    }
+
+    // Creates a label that is unique in the whole program. Labels that start with .L are not put in
+    // the symbol table.
+    fn new_label(&mut self, name: &str) -> String {
+        self.label_count += 1;
+        format!(".L{}_{}", name, self.label_count)
+    }
+
+    // Emits every string literal, with its length (8 bytes) followed by its bytes.
+    fn generate_strings(&mut self, lines: &mut Vec<String>) {
+        if self.strings.is_empty() {
+            return;
+        }
+        lines.push(".section .rodata".to_string());
+        lines.push(".p2align 3".to_string());
+        for (label, bytes) in self.strings.iter() {
+            lines.push(format!("{}:", label));
+            lines.push(format!("    .quad {}", bytes.len()));
+            if !bytes.is_empty() {
+                let bytes: Vec<String> = bytes.iter().map(|byte| byte.to_string()).collect();
+                lines.push(format!("    .byte {}", bytes.join(", ")));
+            }
+            // Keeps the next length aligned.
+            lines.push(".p2align 3".to_string());
+        }
+    }
+
+    // Emits the vtable of every class, with the address of each of its methods. Every object
+    // starts with the address of the vtable of its class, so calling a method through it calls
+    // the method of the class of the object, even when only a base class of it is known.
+    fn generate_vtables(&self, prog: &NodeProg, lines: &mut Vec<String>) {
+        if prog.classes.is_empty() {
+            return;
+        }
+        lines.push(".data".to_string());
+        lines.push(".p2align 3".to_string());
+        for class in prog.classes.iter() {
+            lines.push(format!("{}:", vtable(&class.ident.name)));
+            for method in self.symbols.class(&class.ident.name).vtable.iter() {
+                lines.push(format!("    .quad {}", method.symbol));
+            }
+        }
+        lines.push(".text".to_string());
+    }
+
+    // Hands out the next free stack slot of the frame.
+    fn alloc_slot(&mut self) -> i32 {
+        self.alloc_region(8)
+    }
+
+    // Hands out the next size bytes of the frame (for the elements of an array).
+    fn alloc_region(&mut self, size: i32) -> i32 {
+        self.current_offset -= size;
+        self.frame_size = self.frame_size.max(-self.current_offset);
+        self.current_offset
+    }
+
+    // Finds the stack slot of the variable an identifier refers to.
+    fn slot(&self, ident: &NodeIdent) -> i32 {
+        self.slots[&self.symbols.declaration(ident)]
+    }
+
+    fn generate_function(&mut self, func: &NodeFunc, lines: &mut Vec<String>) {
+        let function = self.symbols.function(&func.ident);
+        let func_name = function.symbol.clone();
+        // A constructor is given the object it initializes as a hidden first argument.
+        let this_type = function
+            .kind
+            .class()
+            .map(|class| TypeMeta::Class(class.to_string()));
+        let constructor = matches!(function.kind, FunctionKind::Constructor(_));
+        let func_global = format!(".global {}", func_name);
+        let func_header = format!("{}:", func_name);
+        lines.push(func_global);
+        lines.push(func_header);
+        // Save the caller's frame pointer and make rbp point to the start of our frame.
+        lines.push("    push rbp".to_string());
+        lines.push("    mov rbp, rsp".to_string());
+        let locations = arg_locations(
+            this_type
+                .iter()
+                .chain(func.params.iter().map(|param| &param.p_type.meta)),
+            ARG_REGISTERS.len(),
+            FLOAT_ARG_REGISTERS.len(),
+        );
+        // The body is generated first, since the size of the frame is only known once every slot
+        // of the function has been handed out.
+        let mut body = Vec::new();
+        // Reset the current offset (and the deepest it got) to 0.
+        self.current_offset = 0;
+        self.frame_size = 0;
+        self.epilogue_label = self.new_label("epilogue");
+        // The parameters passed in registers are stored in stack slots, while the ones passed on the stack are used where
+        // the caller pushed them (right above the saved rbp and the return address).
+        self.slots.clear();
+        let mut locations = locations.into_iter();
+        if this_type.is_some() {
+            // The object is the first argument, so it is always in the first register.
+            locations.next();
+            self.this_offset = self.alloc_slot();
+            body.push(format!(
+                "    mov QWORD PTR [rbp{:+}], {}",
+                self.this_offset, ARG_REGISTERS[0]
+            ));
+        }
+        for (param, location) in func.params.iter().zip(locations) {
+            let offset = match location {
+                ArgLocation::IntRegister(idx) => {
+                    let offset = self.alloc_slot();
+                    body.push(format!(
+                        "    mov QWORD PTR [rbp{:+}], {}",
+                        offset, ARG_REGISTERS[idx]
+                    ));
+                    offset
+                }
+                ArgLocation::FloatRegister(idx) => {
+                    let offset = self.alloc_slot();
+                    body.push(format!(
+                        "    movsd QWORD PTR [rbp{:+}], {}",
+                        offset, FLOAT_ARG_REGISTERS[idx]
+                    ));
+                    offset
+                }
+                ArgLocation::Stack(idx) => 16 + 8 * idx as i32,
+            };
+            self.slots.insert(param.ident.span, offset);
+        }
+        // Arrays are passed as the address of their elements, which are copied into the frame so
+        // that the function cannot change the array of the caller. This is done once every
+        // parameter is stored, since the copy overwrites some of the argument registers.
+        for param in func.params.iter() {
+            if !param.p_type.meta.is_array() {
+                continue;
+            }
+            let size = value_size(&param.p_type.meta);
+            let offset = self.alloc_region(size);
+            body.push(format!(
+                "    mov rsi, QWORD PTR [rbp{:+}]",
+                self.slots[&param.ident.span]
+            ));
+            body.push(format!("    lea rdi, [rbp{:+}]", offset));
+            copy_value(size, &mut body);
+            self.slots.insert(param.ident.span, offset);
+        }
+        // Then, generate the function body (block).
+        self.generate_block(&func.block, &mut body);
+        // A constructor returns the object it initialized.
+        if constructor {
+            body.push(format!(
+                "    mov rax, QWORD PTR [rbp{:+}]",
+                self.this_offset
+            ));
+        }
+        // Now reserve the space for the deepest the slots got, rounded up to 16 bytes. rbp is 16
+        // byte aligned (the call pushed 8 bytes and so did we), so this keeps rsp aligned for the
+        // calls made by the function.
+        let frame_size = (self.frame_size + 15) / 16 * 16;
+        if frame_size > 0 {
+            lines.push(format!("    sub rsp, {}", frame_size));
+        }
+        lines.append(&mut body);
+        // Finally, the epilogue. Every return jumps here with the return value already in rax (or
+        // xmm0 for floats).
+        // Free the frame, restore the caller's frame pointer and return from the function.
+        lines.push(format!("{}:", self.epilogue_label));
+        lines.push("    mov rsp, rbp".to_string());
+        lines.push("    pop rbp".to_string());
+        lines.push("    ret".to_string());
+    }
+
+    fn generate_block(&mut self, block: &NodeBlock, lines: &mut Vec<String>) {
+        // The slots of the variables declared in the block are freed when the block ends, so they
+        // can be reused by the next block (or the next iteration of a loop).
+        let block_offset = self.current_offset;
+        for stmt in block.stmts.iter() {
+            self.generate_stmt(stmt, lines);
+        }
+        self.current_offset = block_offset;
+    }
+
+    fn generate_stmt(&mut self, stmt: &NodeStmt, lines: &mut Vec<String>) {
+        match stmt {
+            NodeStmt::Return(expr, _) if self.symbols.meta_type(expr).is_array() => {
+                // The frame is freed when the function returns, so an array is copied to the heap,
+                // and the caller gets its address.
+                let return_offset = self.current_offset;
+                self.generate_expr(expr, lines);
+                let offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", offset));
+                let size = value_size(self.symbols.meta_type(expr));
+                lines.push(format!("    mov edi, {}", size));
+                lines.push(format!("    call {}", runtime::ALLOC));
+                lines.push("    mov rdi, rax".to_string());
+                lines.push(format!("    mov rsi, QWORD PTR [rbp{:+}]", offset));
+                copy_value(size, lines);
+                self.current_offset = return_offset;
+                lines.push(format!("    jmp {}", self.epilogue_label));
+            }
+            NodeStmt::Return(expr, _) => {
+                // The expression result is left in rax (or xmm0), which is also the return register.
+                self.generate_expr(expr, lines);
+                lines.push(format!("    jmp {}", self.epilogue_label));
+            }
+            NodeStmt::Assign(ident, a_type, expr, _, _) if a_type.meta.is_array() => {
+                // The array gets its space before its value is evaluated, and the value is copied
+                // into it.
+                let offset = self.alloc_region(value_size(&a_type.meta));
+                self.slots.insert(ident.span, offset);
+                self.generate_array_copy(expr, offset, lines);
+            }
+            NodeStmt::Assign(ident, _, expr, _, _) => {
+                self.generate_expr(expr, lines);
+                // Store the expression result onto the stack.
+                let offset = self.alloc_slot();
+                self.slots.insert(ident.span, offset);
+                lines.push(store_slot(self.symbols.variable_type(ident), offset));
+            }
+            NodeStmt::Reassign(ident, expr, _) if self.symbols.meta_type(expr).is_array() => {
+                let offset = self.slot(ident);
+                self.generate_array_copy(expr, offset, lines);
+            }
+            NodeStmt::Reassign(ident, expr, _) => {
+                self.generate_expr(expr, lines);
+                // Store the value into the slot the variable already has.
+                let offset = self.slot(ident);
+                lines.push(store_slot(self.symbols.variable_type(ident), offset));
+            }
+            NodeStmt::If(cond, then_block, else_block, _) => {
+                let else_label = self.new_label("if_else");
+                let end_label = self.new_label("if_end");
+                self.generate_expr(cond, lines);
+                // Any value other than 0 is true.
+                lines.push("    test rax, rax".to_string());
+                lines.push(format!("    jz {}", else_label));
+                self.generate_block(then_block, lines);
+                lines.push(format!("    jmp {}", end_label));
+                lines.push(format!("{}:", else_label));
+                if let Some(else_block) = else_block {
+                    self.generate_block(else_block, lines);
+                }
+                lines.push(format!("{}:", end_label));
+            }
+            NodeStmt::While(cond, body, _) => {
+                let head_label = self.new_label("while_head");
+                let exit_label = self.new_label("while_exit");
+                lines.push(format!("{}:", head_label));
+                self.generate_expr(cond, lines);
+                lines.push("    test rax, rax".to_string());
+                lines.push(format!("    jz {}", exit_label));
+                self.loop_labels
+                    .push((head_label.clone(), exit_label.clone()));
+                self.generate_block(body, lines);
+                self.loop_labels.pop();
+                lines.push(format!("    jmp {}", head_label));
+                lines.push(format!("{}:", exit_label));
+            }
+            // The frame is reserved once for the whole function, so jumping out of the blocks that
+            // variables are declared in does not need to free anything at runtime.
+            NodeStmt::Break(_) => {
+                let (_, exit_label) = self
+                    .loop_labels
+                    .last()
+                    .expect("Error, break outside a loop");
+                lines.push(format!("    jmp {}", exit_label));
+            }
+            NodeStmt::Continue(_) => {
+                let (head_label, _) = self
+                    .loop_labels
+                    .last()
+                    .expect("Error, continue outside a loop");
+                lines.push(format!("    jmp {}", head_label));
+            }
+            NodeStmt::IndexAssign(array, index, expr, _) => {
+                // The address of the array and the index are kept in slots while the value is
+                // evaluated. The index is only checked (and the address of the element computed)
+                // after that, since the value can change the length of a list, and the elements
+                // of a list move when it grows.
+                let stmt_offset = self.current_offset;
+                self.generate_expr(array, lines);
+                let array_offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", array_offset));
+                let array_type = self.symbols.meta_type(array).clone();
+                let element_type = array_type
+                    .element_type()
+                    .expect("Error, indexed a value that is not an array")
+                    .clone();
+                self.generate_expr(index, lines);
+                let index_offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", index_offset));
+                self.generate_expr(expr, lines);
+                lines.push("    mov r8, rax".to_string());
+                lines.push(format!("    mov rax, QWORD PTR [rbp{:+}]", index_offset));
+                self.generate_bounds_check(&array_type, array_offset, lines);
+                self.generate_element_address(&array_type, array_offset, lines);
+                if element_type.is_array() {
+                    lines.push("    mov rdi, rax".to_string());
+                    lines.push("    mov rsi, r8".to_string());
+                    copy_value(value_size(&element_type), lines);
+                } else {
+                    lines.push("    mov rcx, rax".to_string());
+                    lines.push("    mov rax, r8".to_string());
+                    lines.push(store_element(&element_type, "rcx"));
+                }
+                self.current_offset = stmt_offset;
+            }
+            NodeStmt::MemberAssign(object, field, expr, _) => {
+                // The object is kept in a slot while the value is evaluated.
+                let stmt_offset = self.current_offset;
+                self.generate_expr(object, lines);
+                let object_offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", object_offset));
+                self.generate_expr(expr, lines);
+                let offset = field_offset(&self.symbols, object, field);
+                let field_type = self.symbols.meta_type(expr);
+                lines.push(format!("    mov rcx, QWORD PTR [rbp{:+}]", object_offset));
+                if field_type.is_array() {
+                    lines.push(format!("    lea rdi, [rcx + {}]", offset));
+                    lines.push("    mov rsi, rax".to_string());
+                    copy_value(value_size(field_type), lines);
+                } else {
+                    lines.push(store_element(field_type, &format!("rcx + {}", offset)));
+                }
+                self.current_offset = stmt_offset;
+            }
+            NodeStmt::Expr(expr, _) => self.generate_expr(expr, lines),
+        }
+    }
+
+    // Evaluates an array and copies its elements to the ones at an offset in the frame.
+    fn generate_array_copy(&mut self, expr: &NodeExpr, offset: i32, lines: &mut Vec<String>) {
+        let copy_offset = self.current_offset;
+        self.generate_expr(expr, lines);
+        lines.push("    mov rsi, rax".to_string());
+        lines.push(format!("    lea rdi, [rbp{:+}]", offset));
+        copy_value(value_size(self.symbols.meta_type(expr)), lines);
+        self.current_offset = copy_offset;
+    }
+
+    // Aborts the program when the index in rax is not below the length of the array (or list) in
+    // the slot at array_offset. The index is compared as an unsigned number, so a negative index is
+    // out of bounds too.
+    fn generate_bounds_check(
+        &mut self,
+        array_type: &TypeMeta,
+        array_offset: i32,
+        lines: &mut Vec<String>,
+    ) {
+        let ok_label = self.new_label("index_ok");
+        match array_type {
+            TypeMeta::Array(_, len) => lines.push(format!("    mov rcx, {}", len)),
+            // The length of a list is only known when the program runs.
+            _ => {
+                lines.push(format!("    mov rcx, QWORD PTR [rbp{:+}]", array_offset));
+                lines.push("    mov rcx, QWORD PTR [rcx]".to_string());
+            }
+        }
+        lines.push("    cmp rax, rcx".to_string());
+        lines.push(format!("    jb {}", ok_label));
+        lines.push("    mov rdi, rax".to_string());
+        lines.push("    mov rsi, rcx".to_string());
+        lines.push(format!("    call {}", runtime::OUT_OF_BOUNDS));
+        lines.push(format!("{}:", ok_label));
+    }
+
+    // Computes the address of the element at the index in rax (which is in bounds) of the array
+    // (or list) in the slot at array_offset, into rax.
+    fn generate_element_address(
+        &mut self,
+        array_type: &TypeMeta,
+        array_offset: i32,
+        lines: &mut Vec<String>,
+    ) {
+        let element_type = array_type
+            .element_type()
+            .expect("Error, indexed a value that is not an array");
+        lines.push(format!("    imul rax, rax, {}", value_size(element_type)));
+        match array_type {
+            TypeMeta::Array(..) => {
+                lines.push(format!("    add rax, QWORD PTR [rbp{:+}]", array_offset));
+            }
+            _ => {
+                lines.push(format!("    mov rcx, QWORD PTR [rbp{:+}]", array_offset));
+                lines.push("    add rax, QWORD PTR [rcx + 16]".to_string());
+            }
+        }
+    }
+
+    fn generate_expr(&mut self, expr: &NodeExpr, lines: &mut Vec<String>) {
+        match expr {
+            NodeExpr::Literal(val, _) => {
+                // Load the literal into rax. Booleans are 1 (true) or 0 (false), and chars are
+                // their 8 bit code. Floats are loaded into xmm0.
+                match val {
+                    NodeLiteral::IntLit(val) => {
+                        lines.push(format!("    mov rax, {}", val));
+                    }
+                    NodeLiteral::BoolLit(val) => {
+                        lines.push(format!("    mov rax, {}", *val as u8));
+                    }
+                    NodeLiteral::CharLit(val) => {
+                        lines.push(format!("    mov rax, {}", val));
+                    }
+                    // There are no float immediates, so the bits of the float are loaded as an
+                    // integer and moved over.
+                    NodeLiteral::FloatLit(val) => {
+                        lines.push(format!("    mov rax, {}", val.to_bits()));
+                        lines.push("    movq xmm0, rax".to_string());
+                    }
+                    // A string is a pointer to its length, which is followed by its bytes.
+                    NodeLiteral::Str(val) => {
+                        let label = self.new_label("string");
+                        lines.push(format!("    lea rax, [rip + {}]", label));
+                        self.strings.push((label, val.as_bytes().to_vec()));
+                    }
+                }
+            }
+            NodeExpr::Ident(ident) => {
+                // Load the variable value into rax (or xmm0)
+                let offset = self.slot(ident);
+                lines.push(load_slot(self.symbols.variable_type(ident), offset));
+            }
+            NodeExpr::Unary(op, expr, _) => {
+                self.generate_expr(expr, lines);
+                match op {
+                    // Negating a float only flips its sign bit.
+                    UnaryOp::Neg if self.symbols.meta_type(expr).is_float() => {
+                        lines.push("    movq rax, xmm0".to_string());
+                        lines.push("    btc rax, 63".to_string());
+                        lines.push("    movq xmm0, rax".to_string());
+                    }
+                    UnaryOp::Neg => lines.push("    neg rax".to_string()),
+                    UnaryOp::BitNot => lines.push("    not rax".to_string()),
+                    UnaryOp::Not => {
+                        lines.push("    test rax, rax".to_string());
+                        lines.push("    sete al".to_string());
+                        lines.push("    movzx rax, al".to_string());
+                    }
+                }
+            }
+            NodeExpr::Binary(op @ (BinaryOp::And | BinaryOp::Or), lhs, rhs, _) => {
+                // Logical operators short-circuit: the right hand side is only evaluated when the
+                // left hand side does not decide the result on its own. Both sides are turned
+                // into 0 or 1, so the result is always 0 or 1 too.
+                let end_label = self.new_label("logic_end");
+                self.generate_expr(lhs, lines);
+                lines.push("    test rax, rax".to_string());
+                lines.push("    setne al".to_string());
+                lines.push("    movzx rax, al".to_string());
+                // MOVZX does not change the flags set by TEST.
+                match op {
+                    BinaryOp::And => lines.push(format!("    jz {}", end_label)),
+                    _ => lines.push(format!("    jnz {}", end_label)),
+                }
+                self.generate_expr(rhs, lines);
+                lines.push("    test rax, rax".to_string());
+                lines.push("    setne al".to_string());
+                lines.push("    movzx rax, al".to_string());
+                lines.push(format!("{}:", end_label));
+            }
+            NodeExpr::Binary(op, lhs, rhs, _) if self.symbols.meta_type(lhs).is_float() => {
+                // Same as below, with the operands in xmm0 and xmm1.
+                self.generate_expr(lhs, lines);
+                let offset = self.alloc_slot();
+                lines.push(format!("    movsd QWORD PTR [rbp{:+}], xmm0", offset));
+                self.generate_expr(rhs, lines);
+                lines.push("    movapd xmm1, xmm0".to_string());
+                lines.push(format!("    movsd xmm0, QWORD PTR [rbp{:+}]", offset));
+                self.current_offset += 8;
+                match op {
+                    BinaryOp::Mul => lines.push("    mulsd xmm0, xmm1".to_string()),
+                    BinaryOp::Div => lines.push("    divsd xmm0, xmm1".to_string()),
+                    BinaryOp::Add => lines.push("    addsd xmm0, xmm1".to_string()),
+                    BinaryOp::Sub => lines.push("    subsd xmm0, xmm1".to_string()),
+                    // UCOMISD sets the flags like an unsigned comparison, and sets PF too when
+                    // either side is NaN. Every comparison but `!=` has to be false then, which
+                    // `a` and `ae` already are. So `<` and `<=` swap the operands to use them, and
+                    // `==` and `!=` also look at PF. The result of a comparison is a bool, so it
+                    // goes into rax.
+                    BinaryOp::Eq => {
+                        lines.push("    ucomisd xmm0, xmm1".to_string());
+                        lines.push("    sete al".to_string());
+                        lines.push("    setnp cl".to_string());
+                        lines.push("    and al, cl".to_string());
+                        lines.push("    movzx rax, al".to_string());
+                    }
+                    BinaryOp::Ne => {
+                        lines.push("    ucomisd xmm0, xmm1".to_string());
+                        lines.push("    setne al".to_string());
+                        lines.push("    setp cl".to_string());
+                        lines.push("    or al, cl".to_string());
+                        lines.push("    movzx rax, al".to_string());
+                    }
+                    BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                        let (instruction, condition) = match op {
+                            BinaryOp::Lt => ("ucomisd xmm1, xmm0", "a"),
+                            BinaryOp::Le => ("ucomisd xmm1, xmm0", "ae"),
+                            BinaryOp::Gt => ("ucomisd xmm0, xmm1", "a"),
+                            _ => ("ucomisd xmm0, xmm1", "ae"),
+                        };
+                        lines.push(format!("    {}", instruction));
+                        lines.push(format!("    set{} al", condition));
+                        lines.push("    movzx rax, al".to_string());
+                    }
+                    BinaryOp::Mod | BinaryOp::And | BinaryOp::Or => {
+                        unreachable!("Error, {:?} cannot be applied to floats", op)
+                    }
+                }
+            }
+            NodeExpr::Binary(op, lhs, rhs, _)
+                if *self.symbols.meta_type(lhs) == TypeMeta::String =>
+            {
+                // Strings are concatenated and compared by the runtime, which gets the left hand
+                // side in rdi and the right hand side in rsi.
+                self.generate_expr(lhs, lines);
+                let offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", offset));
+                self.generate_expr(rhs, lines);
+                lines.push("    mov rsi, rax".to_string());
+                lines.push(format!("    mov rdi, QWORD PTR [rbp{:+}]", offset));
+                self.current_offset += 8;
+                let routine = match op {
+                    BinaryOp::Add => runtime::STRING_CONCAT,
+                    BinaryOp::Eq | BinaryOp::Ne => runtime::STRING_EQ,
+                    _ => unreachable!("Error, {:?} cannot be applied to strings", op),
+                };
+                lines.push(format!("    call {}", routine));
+                if *op == BinaryOp::Ne {
+                    lines.push("    xor rax, 1".to_string());
+                }
+            }
+            NodeExpr::Binary(op, lhs, rhs, _) => {
+                // The left hand side is spilled to a temporary stack slot while the right hand
+                // side is evaluated, since evaluating it will overwrite rax.
+                self.generate_expr(lhs, lines);
+                let offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", offset));
+                self.generate_expr(rhs, lines);
+                lines.push("    mov rcx, rax".to_string());
+                lines.push(format!("    mov rax, QWORD PTR [rbp{:+}]", offset));
+                self.current_offset += 8;
+                match op {
+                    BinaryOp::Mul => lines.push("    imul rax, rcx".to_string()),
+                    BinaryOp::Div | BinaryOp::Mod => {
+                        // IDIV traps when the divisor is zero, and when the quotient does not fit
+                        // (the smallest int divided by -1). Dividing by zero aborts the program,
+                        // and dividing by -1 is a negation, which wraps the smallest int around
+                        // to itself (like SDIV on aarch64), with a remainder of 0.
+                        let ok_label = self.new_label("div_ok");
+                        let div_label = self.new_label("div");
+                        let end_label = self.new_label("div_end");
+                        lines.push("    test rcx, rcx".to_string());
+                        lines.push(format!("    jnz {}", ok_label));
+                        lines.push(format!("    call {}", runtime::DIV_BY_ZERO));
+                        lines.push(format!("{}:", ok_label));
+                        lines.push("    cmp rcx, -1".to_string());
+                        lines.push(format!("    jne {}", div_label));
+                        if *op == BinaryOp::Mod {
+                            lines.push("    xor eax, eax".to_string());
+                        } else {
+                            lines.push("    neg rax".to_string());
+                        }
+                        lines.push(format!("    jmp {}", end_label));
+                        // IDIV divides rdx:rax by its operand, so rax is sign extended into rdx
+                        // first. The quotient ends up in rax and the remainder in rdx.
+                        lines.push(format!("{}:", div_label));
+                        lines.push("    cqo".to_string());
+                        lines.push("    idiv rcx".to_string());
+                        if *op == BinaryOp::Mod {
+                            lines.push("    mov rax, rdx".to_string());
+                        }
+                        lines.push(format!("{}:", end_label));
+                    }
+                    BinaryOp::Add => lines.push("    add rax, rcx".to_string()),
+                    BinaryOp::Sub => lines.push("    sub rax, rcx".to_string()),
+                    BinaryOp::Eq
+                    | BinaryOp::Ne
+                    | BinaryOp::Lt
+                    | BinaryOp::Le
+                    | BinaryOp::Gt
+                    | BinaryOp::Ge => {
+                        lines.push("    cmp rax, rcx".to_string());
+                        lines.push(format!("    set{} al", condition_code(*op)));
+                        lines.push("    movzx rax, al".to_string());
+                    }
+                    BinaryOp::And | BinaryOp::Or => {
+                        unreachable!("Error, logical operators are short-circuited")
+                    }
+                }
+            }
+            NodeExpr::Paren(expr, _) => self.generate_expr(expr, lines),
+            NodeExpr::Call(ident, args, _) => {
+                self.generate_call(ident, args, lines);
+            }
+            NodeExpr::MethodCall(object, method, args, _) => {
+                self.generate_method_call(object, method, args, lines);
+            }
+            // An array literal is evaluated into its own space in the frame, and its value is the
+            // address of its elements. That space is kept until the statement (or call) that uses
+            // the array is done with it.
+            NodeExpr::Array(elements, _) => {
+                let meta_type = self.symbols.meta_type(expr).clone();
+                let offset = self.alloc_region(value_size(&meta_type));
+                let mut element_offset = offset;
+                for element in elements.iter() {
+                    let element_start = self.current_offset;
+                    self.generate_expr(element, lines);
+                    let element_type = self.symbols.meta_type(element);
+                    if element_type.is_array() {
+                        lines.push("    mov rsi, rax".to_string());
+                        lines.push(format!("    lea rdi, [rbp{:+}]", element_offset));
+                        copy_value(value_size(element_type), lines);
+                    } else {
+                        lines.push(spill_slot(element_type, element_offset));
+                    }
+                    element_offset += value_size(element_type);
+                    self.current_offset = element_start;
+                }
+                lines.push(format!("    lea rax, [rbp{:+}]", offset));
+            }
+            NodeExpr::ArrayRepeat(value, count, _) => {
+                // The value is evaluated once, and stored into every element by a loop (r8 points
+                // to the next element, and r9 counts the elements that are left).
+                let meta_type = self.symbols.meta_type(expr).clone();
+                let offset = self.alloc_region(value_size(&meta_type));
+                let value_start = self.current_offset;
+                self.generate_expr(value, lines);
+                let loop_label = self.new_label("array_repeat");
+                lines.push(format!("    lea r8, [rbp{:+}]", offset));
+                lines.push(format!("    mov r9, {}", count));
+                lines.push(format!("{}:", loop_label));
+                let value_type = self.symbols.meta_type(value);
+                let size = value_size(value_type);
+                if value_type.is_array() {
+                    lines.push("    mov rdi, r8".to_string());
+                    lines.push("    mov rsi, rax".to_string());
+                    copy_value(size, lines);
+                } else {
+                    lines.push(store_element(value_type, "r8"));
+                }
+                lines.push(format!("    add r8, {}", size));
+                lines.push("    dec r9".to_string());
+                lines.push(format!("    jnz {}", loop_label));
+                self.current_offset = value_start;
+                lines.push(format!("    lea rax, [rbp{:+}]", offset));
+            }
+            NodeExpr::Index(array, index, _) => {
+                // The address of the array is spilled while the index is evaluated. An element
+                // that is an array is used by its address, like any other array, so it keeps the
+                // array it is in.
+                let index_start = self.current_offset;
+                self.generate_expr(array, lines);
+                let array_end = self.current_offset;
+                let offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", offset));
+                let array_type = self.symbols.meta_type(array).clone();
+                let element_type = array_type
+                    .element_type()
+                    .expect("Error, indexed a value that is not an array")
+                    .clone();
+                self.generate_expr(index, lines);
+                self.generate_bounds_check(&array_type, offset, lines);
+                self.generate_element_address(&array_type, offset, lines);
+                if element_type.is_array() {
+                    self.current_offset = array_end;
+                } else {
+                    lines.push(load_element(&element_type, "rax"));
+                    self.current_offset = index_start;
+                }
+            }
+            NodeExpr::EmptyList(_) => {
+                lines.push(format!("    call {}", runtime::LIST_NEW));
+            }
+            NodeExpr::This(_) | NodeExpr::Super(_) => {
+                lines.push(format!(
+                    "    mov rax, QWORD PTR [rbp{:+}]",
+                    self.this_offset
+                ));
+            }
+            // The constructor of the base class initializes `this`, which is its own slot.
+            NodeExpr::SuperCall(keyword, args, _) => {
+                let call_offset = self.current_offset;
+                let function = self.symbols.function(keyword).clone();
+                self.generate_call_to(&function, args, vec![self.this_offset], None, lines);
+                self.current_offset = call_offset;
+            }
+            // A field that is an array is used by its address, like an element of an array. The
+            // variant of an enum is its value, which is a constant.
+            NodeExpr::Member(object, field, _) => {
+                if let Some(value) = self.symbols.variant(field) {
+                    lines.push(format!("    mov rax, {}", value));
+                    return;
+                }
+                self.generate_expr(object, lines);
+                let offset = field_offset(&self.symbols, object, field);
+                let field_type = self.symbols.meta_type(expr);
+                if field_type.is_array() {
+                    lines.push(format!("    add rax, {}", offset));
+                } else {
+                    lines.push(load_element(field_type, &format!("rax + {}", offset)));
+                }
+            }
+        }
+    }
+
+    fn generate_call(&mut self, ident: &NodeIdent, args: &[NodeExpr], lines: &mut Vec<String>) {
+        match self.symbols.function(ident).symbol.as_str() {
+            LIST_PUSH | LIST_POP | LIST_LEN => return self.generate_list_call(ident, args, lines),
+            _ => {}
+        }
+        let call_offset = self.current_offset;
+        let function = self.symbols.function(ident).clone();
+        let mut arg_offsets: Vec<i32> = Vec::new();
+        // A constructor is given a new object, which it initializes and returns.
+        if let FunctionKind::Constructor(class) = &function.kind {
+            lines.push(format!(
+                "    mov edi, {}",
+                object_size(&self.symbols, class)
+            ));
+            lines.push(format!("    call {}", runtime::ALLOC));
+            lines.push(format!("    lea rcx, [rip + {}]", vtable(class)));
+            lines.push("    mov QWORD PTR [rax], rcx".to_string());
+            let offset = self.alloc_slot();
+            arg_offsets.push(offset);
+            lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", offset));
+        }
+        self.generate_call_to(&function, args, arg_offsets, None, lines);
+        self.current_offset = call_offset;
+    }
+
+    // Generates a call to a method, which is given the object it is called on as its first
+    // argument.
+    fn generate_method_call(
+        &mut self,
+        object: &NodeExpr,
+        method: &NodeIdent,
+        args: &[NodeExpr],
+        lines: &mut Vec<String>,
+    ) {
+        let call_offset = self.current_offset;
+        self.generate_expr(object, lines);
+        let offset = self.alloc_slot();
+        lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", offset));
+        let function = self.symbols.function(method).clone();
+        // A method called through `super` is the one of the base class, any other is looked up in
+        // the vtable of the object.
+        let dispatch = match object {
+            NodeExpr::Super(_) => None,
+            _ => Some(vtable_offset(&self.symbols, object, &function)),
+        };
+        self.generate_call_to(&function, args, vec![offset], dispatch, lines);
+        self.current_offset = call_offset;
+    }
+
+    // Calls a function with the values in the slots at arg_offsets (the object of a constructor or
+    // a method), followed by the arguments. Every argument is evaluated (from left to right) into
+    // its own temporary stack slot, since evaluating one argument could overwrite the argument
+    // registers. A method is called through the vtable of its object when dispatch is the offset of
+    // the method in it.
+    fn generate_call_to(
+        &mut self,
+        function: &FunctionSymbol,
+        args: &[NodeExpr],
+        mut arg_offsets: Vec<i32>,
+        dispatch: Option<i32>,
+        lines: &mut Vec<String>,
+    ) {
+        let mut params = function.params.clone();
+        if let Some(class) = function.kind.class() {
+            params.insert(0, TypeMeta::Class(class.to_string()));
+        }
+        for arg in args.iter() {
+            self.generate_expr(arg, lines);
+            let offset = self.alloc_slot();
+            arg_offsets.push(offset);
+            lines.push(spill_slot(self.symbols.meta_type(arg), offset));
+        }
+        let symbol = &function.symbol;
+        let locations = arg_locations(
+            params.iter(),
+            ARG_REGISTERS.len(),
+            FLOAT_ARG_REGISTERS.len(),
+        );
+        // rsp has to be 16 byte aligned at the call, after the stack arguments are pushed.
+        let stack_offsets: Vec<i32> = locations
+            .iter()
+            .zip(arg_offsets.iter())
+            .filter(|(location, _)| matches!(location, ArgLocation::Stack(_)))
+            .map(|(_, offset)| *offset)
+            .collect();
+        let stack_args = stack_offsets.len();
+        let padding = if stack_args % 2 == 1 { 8 } else { 0 };
+        if padding > 0 {
+            lines.push(format!("    sub rsp, {}", padding));
+        }
+        // Stack arguments are pushed from last to first, so the first one ends up on top. A float
+        // on the stack is just its 8 bytes, so it is pushed like an integer.
+        for offset in stack_offsets.iter().rev() {
+            lines.push(format!("    push QWORD PTR [rbp{:+}]", offset));
+        }
+        for (location, offset) in locations.into_iter().zip(arg_offsets) {
+            match location {
+                ArgLocation::IntRegister(idx) => lines.push(format!(
+                    "    mov {}, QWORD PTR [rbp{:+}]",
+                    ARG_REGISTERS[idx], offset
+                )),
+                ArgLocation::FloatRegister(idx) => lines.push(format!(
+                    "    movsd {}, QWORD PTR [rbp{:+}]",
+                    FLOAT_ARG_REGISTERS[idx], offset
+                )),
+                ArgLocation::Stack(_) => {}
+            }
+        }
+        match dispatch {
+            // The object is the first argument, and r11 is not used to pass arguments.
+            Some(offset) => {
+                lines.push("    mov r11, QWORD PTR [rdi]".to_string());
+                lines.push(format!("    call QWORD PTR [r11 + {}]", offset));
+            }
+            None => lines.push(format!("    call {}", symbol)),
+        }
+        // Pop the stack arguments. The return value is already in rax (or xmm0), like the result of
+        // any other expression.
+        let stack_args_size = 8 * stack_args + padding;
+        if stack_args_size > 0 {
+            lines.push(format!("    add rsp, {}", stack_args_size));
+        }
+    }
+
+    // Generates a call to `push`, `pop` or `len` on a list. The runtime grows and shrinks the
+    // list, and gives back the address of the element that is pushed or popped, which is stored
+    // or loaded here since that depends on the type of the elements.
+    fn generate_list_call(
+        &mut self,
+        ident: &NodeIdent,
+        args: &[NodeExpr],
+        lines: &mut Vec<String>,
+    ) {
+        let call_offset = self.current_offset;
+        let function = self.symbols.function(ident).clone();
+        let element_type = function.params[0]
+            .element_type()
+            .expect("Error, list function called on a value that is not a list")
+            .clone();
+        let size = value_size(&element_type);
+        self.generate_expr(&args[0], lines);
+        match function.symbol.as_str() {
+            LIST_PUSH => {
+                let list_offset = self.alloc_slot();
+                lines.push(format!("    mov QWORD PTR [rbp{:+}], rax", list_offset));
+                self.generate_expr(&args[1], lines);
+                let value_offset = self.alloc_slot();
+                lines.push(spill_slot(&element_type, value_offset));
+                lines.push(format!("    mov rdi, QWORD PTR [rbp{:+}]", list_offset));
+                lines.push(format!("    mov esi, {}", size));
+                lines.push(format!("    call {}", runtime::LIST_PUSH));
+                lines.push("    mov rdi, rax".to_string());
+                if element_type.is_array() {
+                    lines.push(format!("    mov rsi, QWORD PTR [rbp{:+}]", value_offset));
+                    copy_value(size, lines);
+                } else {
+                    lines.push(format!("    mov rax, QWORD PTR [rbp{:+}]", value_offset));
+                    lines.push("    mov QWORD PTR [rdi], rax".to_string());
+                }
+            }
+            // An element that is an array is used by its address, which stays valid until something
+            // is pushed to the list again.
+            LIST_POP => {
+                lines.push("    mov rdi, rax".to_string());
+                lines.push(format!("    mov esi, {}", size));
+                lines.push(format!("    call {}", runtime::LIST_POP));
+                if !element_type.is_array() {
+                    lines.push(load_element(&element_type, "rax"));
+                }
+            }
+            _ => lines.push("    mov rax, QWORD PTR [rax]".to_string()),
+        }
+        self.current_offset = call_offset;
+    }
+}
+
+// Loads a variable of a type from its slot into rax (or xmm0). Chars are a single byte, which is
+// zero extended. The value of an array is the address of its elements.
+fn load_slot(meta_type: &TypeMeta, offset: i32) -> String {
+    match meta_type {
+        TypeMeta::Array(..) => format!("    lea rax, [rbp{:+}]", offset),
+        TypeMeta::Primitive(PrimitiveType::Float) => {
+            format!("    movsd xmm0, QWORD PTR [rbp{:+}]", offset)
+        }
+        TypeMeta::Primitive(PrimitiveType::Char) => {
+            format!("    movzx eax, BYTE PTR [rbp{:+}]", offset)
+        }
+        _ => format!("    mov rax, QWORD PTR [rbp{:+}]", offset),
+    }
+}
+
+// Stores rax (or xmm0) into the slot of a variable of a type.
+fn store_slot(meta_type: &TypeMeta, offset: i32) -> String {
+    match meta_type {
+        TypeMeta::Primitive(PrimitiveType::Char) => {
+            format!("    mov BYTE PTR [rbp{:+}], al", offset)
+        }
+        _ => spill_slot(meta_type, offset),
+    }
+}
+
+// Stores all 8 bytes of rax (or xmm0) into a temporary slot.
+fn spill_slot(meta_type: &TypeMeta, offset: i32) -> String {
+    if meta_type.is_float() {
+        format!("    movsd QWORD PTR [rbp{:+}], xmm0", offset)
+    } else {
+        format!("    mov QWORD PTR [rbp{:+}], rax", offset)
+    }
+}
+
+// Loads an element of an array (that is not an array itself) from the address in a register into
+// rax (or xmm0). Every element takes 8 bytes, even chars.
+fn load_element(meta_type: &TypeMeta, address: &str) -> String {
+    if meta_type.is_float() {
+        format!("    movsd xmm0, QWORD PTR [{}]", address)
+    } else {
+        format!("    mov rax, QWORD PTR [{}]", address)
+    }
+}
+
+// Stores rax (or xmm0) into the element of an array at the address in a register.
+fn store_element(meta_type: &TypeMeta, address: &str) -> String {
+    if meta_type.is_float() {
+        format!("    movsd QWORD PTR [{}], xmm0", address)
+    } else {
+        format!("    mov QWORD PTR [{}], rax", address)
+    }
+}
+
+// Copies a value of size bytes (an array) from the address in rsi to the address in rdi. REP
+// MOVSQ copies rcx quadwords, and leaves rsi and rdi right after the copied bytes.
+fn copy_value(size: i32, lines: &mut Vec<String>) {
+    lines.push(format!("    mov ecx, {}", size / 8));
+    lines.push("    rep movsq".to_string());
+}
+
+// The condition code that is true when the comparison is (after a `cmp lhs, rhs`).
+fn condition_code(op: BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Eq => "e",
+        BinaryOp::Ne => "ne",
+        BinaryOp::Lt => "l",
+        BinaryOp::Le => "le",
+        BinaryOp::Gt => "g",
+        BinaryOp::Ge => "ge",
+        _ => unreachable!("Error, {:?} is not a comparison", op),
+    }
+}
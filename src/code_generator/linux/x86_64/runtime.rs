@@ -0,0 +1,489 @@
+// The runtime is a handful of routines written in assembly, which are emitted along with every
+// program. They follow the same calling convention as the functions of the program, and only use
+// the registers a callee is allowed to overwrite.
+
+// The symbols of the runtime routines. Functions of the program are all prefixed with
+// `objection_`, so these cannot clash with them. The builtin functions are called like any other
+// function, through the symbol the semantic analysis gives them.
+pub const ALLOC: &str = "__objection_alloc";
+pub const STRING_CONCAT: &str = "__objection_string_concat";
+pub const STRING_EQ: &str = "__objection_string_eq";
+const LEN: &str = "__objection_len";
+const PRINT_INT: &str = "__objection_print_int";
+const PRINT_FLOAT: &str = "__objection_print_float";
+const PRINT_BOOL: &str = "__objection_print_bool";
+const PRINT_CHAR: &str = "__objection_print_char";
+const PRINT_STRING: &str = "__objection_print_string";
+pub const OUT_OF_BOUNDS: &str = "__objection_out_of_bounds";
+pub const DIV_BY_ZERO: &str = "__objection_div_by_zero";
+pub const LIST_NEW: &str = "__objection_list_new";
+pub const LIST_PUSH: &str = "__objection_list_push";
+pub const LIST_POP: &str = "__objection_list_pop";
+const WRITE_DIGITS: &str = "__objection_write_digits";
+
+// The file descriptors of the standard output and the standard error.
+const STDOUT: &str = "1";
+const STDERR: &str = "2";
+
+// The exit code of a program that is aborted because it indexed an array (or a list) out of its
+// bounds, or popped from an empty list.
+const OUT_OF_BOUNDS_EXIT_CODE: u32 = 101;
+
+// The exit code of a program that is aborted because it divided an int by zero (with `/` or `%`).
+const DIV_BY_ZERO_EXIT_CODE: u32 = 102;
+
+// The exit code of a program that is aborted because the OS did not give it more memory for the
+// heap.
+const OUT_OF_MEMORY_EXIT_CODE: u32 = 103;
+
+// The number of elements a list has room for once something is pushed to it. It doubles every
+// time the list runs out of room.
+const LIST_CAPACITY: u32 = 4;
+
+// The heap grows in chunks of (at least) this many bytes, which are requested with mmap.
+const HEAP_CHUNK: u32 = 0x100000;
+
+// The strings the runtime prints are added to the string literals of the program, as (label, bytes).
+pub fn generate_runtime(lines: &mut Vec<String>, strings: &mut Vec<(String, Vec<u8>)>) {
+    generate_alloc(lines);
+    generate_string_concat(lines);
+    generate_string_eq(lines);
+    generate_len(lines);
+    generate_print_string(lines);
+    generate_print_char(lines);
+    generate_print_bool(lines);
+    generate_write_digits(lines);
+    generate_print_int(lines);
+    generate_print_float(lines);
+    generate_out_of_bounds(lines);
+    generate_div_by_zero(lines);
+    generate_list_new(lines);
+    generate_list_push(lines);
+    generate_list_pop(lines);
+    for (name, value) in RUNTIME_STRINGS {
+        strings.push((format!(".Lruntime_{}", name), value.as_bytes().to_vec()));
+    }
+    // The next free byte of the heap and the end of the current chunk, which start out empty.
+    lines.push(".data".to_string());
+    lines.push(".p2align 3".to_string());
+    lines.push(".Lheap:".to_string());
+    lines.push("    .quad 0".to_string());
+    lines.push("    .quad 0".to_string());
+    lines.push(".text".to_string());
+}
+
+// alloc(size) returns a pointer to size bytes of memory, aligned to 8 bytes. It is a bump
+// allocator: memory is handed out from the current chunk, and never freed. When the chunk runs
+// out, a new one is mapped (and the rest of the old one is wasted). When the OS cannot map a new
+// chunk, the program is aborted.
+fn generate_alloc(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", ALLOC));
+    lines.push("    add rdi, 7".to_string());
+    lines.push("    and rdi, -8".to_string());
+    lines.push("    mov rax, QWORD PTR [rip + .Lheap]".to_string());
+    lines.push("    lea rdx, [rax + rdi]".to_string());
+    lines.push("    cmp rdx, QWORD PTR [rip + .Lheap + 8]".to_string());
+    lines.push("    ja .Lalloc_grow".to_string());
+    lines.push("    mov QWORD PTR [rip + .Lheap], rdx".to_string());
+    lines.push("    ret".to_string());
+    // The new chunk is big enough for the allocation. The size and the length of the chunk are
+    // kept on the stack during the syscall, which overwrites rcx and r11.
+    lines.push(".Lalloc_grow:".to_string());
+    lines.push(format!("    mov esi, {}", HEAP_CHUNK));
+    lines.push("    cmp rdi, rsi".to_string());
+    lines.push("    cmova rsi, rdi".to_string());
+    lines.push("    push rdi".to_string());
+    lines.push("    push rsi".to_string());
+    // mmap(NULL, length, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0)
+    lines.push("    xor edi, edi".to_string());
+    lines.push("    mov edx, 3".to_string());
+    lines.push("    mov r10d, 0x22".to_string());
+    lines.push("    mov r8, -1".to_string());
+    lines.push("    xor r9d, r9d".to_string());
+    lines.push("    mov eax, 9".to_string());
+    lines.push("    syscall".to_string());
+    // A failed syscall returns -errno, which is between -4095 and -1.
+    lines.push("    cmp rax, -4096".to_string());
+    lines.push("    ja .Lalloc_failed".to_string());
+    lines.push("    pop rsi".to_string());
+    lines.push("    pop rdi".to_string());
+    lines.push("    lea rdx, [rax + rdi]".to_string());
+    lines.push("    mov QWORD PTR [rip + .Lheap], rdx".to_string());
+    lines.push("    add rsi, rax".to_string());
+    lines.push("    mov QWORD PTR [rip + .Lheap + 8], rsi".to_string());
+    lines.push("    ret".to_string());
+    lines.push(".Lalloc_failed:".to_string());
+    write_string(lines, "out_of_memory", STDERR);
+    lines.push(format!("    mov edi, {}", OUT_OF_MEMORY_EXIT_CODE));
+    lines.push("    mov eax, 60".to_string());
+    lines.push("    syscall".to_string());
+}
+
+// string_concat(lhs, rhs) returns a new string with the bytes of lhs followed by the bytes of rhs.
+fn generate_string_concat(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", STRING_CONCAT));
+    // Both strings are kept on the stack while alloc is called, which also keeps rsp aligned.
+    lines.push("    push rdi".to_string());
+    lines.push("    push rsi".to_string());
+    lines.push("    sub rsp, 8".to_string());
+    lines.push("    mov rdi, QWORD PTR [rdi]".to_string());
+    lines.push("    add rdi, QWORD PTR [rsi]".to_string());
+    lines.push("    add rdi, 8".to_string());
+    lines.push(format!("    call {}", ALLOC));
+    lines.push("    add rsp, 8".to_string());
+    lines.push("    pop rdx".to_string());
+    lines.push("    pop rsi".to_string());
+    // REP MOVSB copies rcx bytes from rsi to rdi, and leaves rdi right after the copied bytes.
+    lines.push("    mov rcx, QWORD PTR [rsi]".to_string());
+    lines.push("    mov r8, QWORD PTR [rdx]".to_string());
+    lines.push("    lea r9, [rcx + r8]".to_string());
+    lines.push("    mov QWORD PTR [rax], r9".to_string());
+    lines.push("    lea rdi, [rax + 8]".to_string());
+    lines.push("    add rsi, 8".to_string());
+    lines.push("    rep movsb".to_string());
+    lines.push("    lea rsi, [rdx + 8]".to_string());
+    lines.push("    mov rcx, r8".to_string());
+    lines.push("    rep movsb".to_string());
+    lines.push("    ret".to_string());
+}
+
+// string_eq(lhs, rhs) returns 1 if both strings have the same bytes, and 0 otherwise.
+fn generate_string_eq(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", STRING_EQ));
+    lines.push("    mov rcx, QWORD PTR [rdi]".to_string());
+    lines.push("    cmp rcx, QWORD PTR [rsi]".to_string());
+    lines.push("    jne .Lstring_eq_false".to_string());
+    // REPE CMPSB compares the bytes at rsi and rdi until they differ or rcx bytes were compared.
+    // LEA does not change the flags, so they are still equal if both strings are empty.
+    lines.push("    lea rsi, [rsi + 8]".to_string());
+    lines.push("    lea rdi, [rdi + 8]".to_string());
+    lines.push("    repe cmpsb".to_string());
+    lines.push("    jne .Lstring_eq_false".to_string());
+    lines.push("    mov eax, 1".to_string());
+    lines.push("    ret".to_string());
+    lines.push(".Lstring_eq_false:".to_string());
+    lines.push("    xor eax, eax".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin len(s: string): int, which returns the number of bytes in a string.
+fn generate_len(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", LEN));
+    lines.push("    mov rax, QWORD PTR [rdi]".to_string());
+    lines.push("    ret".to_string());
+}
+
+// Makes the write syscall, which writes rdx bytes from rsi to a file descriptor (an immediate or a
+// register). The syscall overwrites rcx and r11.
+fn write(lines: &mut Vec<String>, fd: &str) {
+    lines.push(format!("    mov rdi, {}", fd));
+    lines.push("    mov eax, 1".to_string());
+    lines.push("    syscall".to_string());
+}
+
+// The builtin print(s: string).
+fn generate_print_string(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", PRINT_STRING));
+    lines.push("    mov rdx, QWORD PTR [rdi]".to_string());
+    lines.push("    lea rsi, [rdi + 8]".to_string());
+    write(lines, STDOUT);
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(c: char). The char is written from the stack.
+fn generate_print_char(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", PRINT_CHAR));
+    lines.push("    push rdi".to_string());
+    lines.push("    mov rsi, rsp".to_string());
+    lines.push("    mov edx, 1".to_string());
+    write(lines, STDOUT);
+    lines.push("    pop rdi".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(b: bool), which prints `true` or `false`.
+fn generate_print_bool(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", PRINT_BOOL));
+    lines.push("    test rdi, rdi".to_string());
+    lines.push("    jz .Lprint_bool_false".to_string());
+    lines.push("    lea rdi, [rip + .Lruntime_true]".to_string());
+    lines.push(format!("    jmp {}", PRINT_STRING));
+    lines.push(".Lprint_bool_false:".to_string());
+    lines.push("    lea rdi, [rip + .Lruntime_false]".to_string());
+    lines.push(format!("    jmp {}", PRINT_STRING));
+}
+
+// write_digits(value, min_digits, fd) writes an unsigned number in decimal to a file descriptor,
+// with leading zeros if it has less than min_digits digits. The digits are put in a buffer on the
+// stack from the last one to the first one, and then written all at once. DIV overwrites rdx, so
+// the file descriptor is kept in r10.
+fn generate_write_digits(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", WRITE_DIGITS));
+    lines.push("    mov r10, rdx".to_string());
+    lines.push("    sub rsp, 32".to_string());
+    lines.push("    lea r8, [rsp + 32]".to_string());
+    lines.push("    mov rax, rdi".to_string());
+    lines.push("    mov r9d, 10".to_string());
+    // DIV divides rdx:rax, and leaves the quotient in rax and the remainder in rdx.
+    lines.push(".Lwrite_digits_loop:".to_string());
+    lines.push("    xor edx, edx".to_string());
+    lines.push("    div r9".to_string());
+    lines.push("    add dl, 48".to_string());
+    lines.push("    dec r8".to_string());
+    lines.push("    mov BYTE PTR [r8], dl".to_string());
+    lines.push("    dec rsi".to_string());
+    lines.push("    test rax, rax".to_string());
+    lines.push("    jnz .Lwrite_digits_loop".to_string());
+    lines.push("    test rsi, rsi".to_string());
+    lines.push("    jg .Lwrite_digits_loop".to_string());
+    lines.push("    lea rdx, [rsp + 32]".to_string());
+    lines.push("    sub rdx, r8".to_string());
+    lines.push("    mov rsi, r8".to_string());
+    write(lines, "r10");
+    lines.push("    add rsp, 32".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(n: int). Negating the smallest int overflows back to itself, which is still
+// the right number once it is treated as unsigned.
+fn generate_print_int(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", PRINT_INT));
+    lines.push("    sub rsp, 8".to_string());
+    lines.push("    test rdi, rdi".to_string());
+    lines.push("    jns .Lprint_int_positive".to_string());
+    lines.push("    neg rdi".to_string());
+    lines.push("    mov QWORD PTR [rsp], rdi".to_string());
+    lines.push("    mov edi, 45".to_string());
+    lines.push(format!("    call {}", PRINT_CHAR));
+    lines.push("    mov rdi, QWORD PTR [rsp]".to_string());
+    lines.push(".Lprint_int_positive:".to_string());
+    lines.push("    mov esi, 1".to_string());
+    lines.push(format!("    mov edx, {}", STDOUT));
+    lines.push(format!("    call {}", WRITE_DIGITS));
+    lines.push("    add rsp, 8".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The builtin print(x: float), which prints the float with 6 decimals (like `%f` in C), or `nan`
+// or `inf`. Floats that are too big for an int are divided by 10 until they fit, and the digits
+// that were divided away are printed as zeros, so they are only precise up to their first 18
+// digits.
+fn generate_print_float(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", PRINT_FLOAT));
+    lines.push("    sub rsp, 24".to_string());
+    // NaN is the only float that is unordered with itself.
+    lines.push("    ucomisd xmm0, xmm0".to_string());
+    lines.push("    jp .Lprint_float_nan".to_string());
+    lines.push("    movq rax, xmm0".to_string());
+    lines.push("    test rax, rax".to_string());
+    lines.push("    jns .Lprint_float_positive".to_string());
+    lines.push("    btc rax, 63".to_string());
+    lines.push("    mov QWORD PTR [rsp], rax".to_string());
+    lines.push("    mov edi, 45".to_string());
+    lines.push(format!("    call {}", PRINT_CHAR));
+    lines.push("    movq xmm0, QWORD PTR [rsp]".to_string());
+    lines.push(".Lprint_float_positive:".to_string());
+    lines.push(format!("    mov rax, {}", f64::INFINITY.to_bits()));
+    lines.push("    movq xmm1, rax".to_string());
+    lines.push("    ucomisd xmm0, xmm1".to_string());
+    lines.push("    je .Lprint_float_inf".to_string());
+    // rcx counts the digits that were divided away.
+    lines.push(format!("    mov rax, {}", 1e18f64.to_bits()));
+    lines.push("    movq xmm1, rax".to_string());
+    lines.push(format!("    mov rax, {}", 10f64.to_bits()));
+    lines.push("    movq xmm2, rax".to_string());
+    lines.push("    xor ecx, ecx".to_string());
+    lines.push(".Lprint_float_scale:".to_string());
+    lines.push("    ucomisd xmm0, xmm1".to_string());
+    lines.push("    jb .Lprint_float_scaled".to_string());
+    lines.push("    divsd xmm0, xmm2".to_string());
+    lines.push("    inc rcx".to_string());
+    lines.push("    jmp .Lprint_float_scale".to_string());
+    // The integer part goes in rdi and the decimals (rounded to 6 digits, by adding a half and
+    // truncating) in rdx. Rounding can carry into the integer part (0.9999999 is 1.000000).
+    lines.push(".Lprint_float_scaled:".to_string());
+    lines.push("    cvttsd2si rdi, xmm0".to_string());
+    lines.push("    cvtsi2sd xmm1, rdi".to_string());
+    lines.push("    subsd xmm0, xmm1".to_string());
+    lines.push(format!("    mov rax, {}", 1e6f64.to_bits()));
+    lines.push("    movq xmm1, rax".to_string());
+    lines.push("    mulsd xmm0, xmm1".to_string());
+    lines.push(format!("    mov rax, {}", 0.5f64.to_bits()));
+    lines.push("    movq xmm1, rax".to_string());
+    lines.push("    addsd xmm0, xmm1".to_string());
+    lines.push("    cvttsd2si rdx, xmm0".to_string());
+    lines.push("    cmp rdx, 1000000".to_string());
+    lines.push("    jb .Lprint_float_rounded".to_string());
+    lines.push("    xor edx, edx".to_string());
+    lines.push("    inc rdi".to_string());
+    lines.push(".Lprint_float_rounded:".to_string());
+    lines.push("    mov QWORD PTR [rsp], rdx".to_string());
+    lines.push("    mov QWORD PTR [rsp + 8], rcx".to_string());
+    lines.push("    mov esi, 1".to_string());
+    lines.push(format!("    mov edx, {}", STDOUT));
+    lines.push(format!("    call {}", WRITE_DIGITS));
+    lines.push(".Lprint_float_zeros:".to_string());
+    lines.push("    mov rcx, QWORD PTR [rsp + 8]".to_string());
+    lines.push("    test rcx, rcx".to_string());
+    lines.push("    jz .Lprint_float_point".to_string());
+    lines.push("    dec rcx".to_string());
+    lines.push("    mov QWORD PTR [rsp + 8], rcx".to_string());
+    lines.push("    mov edi, 48".to_string());
+    lines.push(format!("    call {}", PRINT_CHAR));
+    lines.push("    jmp .Lprint_float_zeros".to_string());
+    lines.push(".Lprint_float_point:".to_string());
+    lines.push("    mov edi, 46".to_string());
+    lines.push(format!("    call {}", PRINT_CHAR));
+    lines.push("    mov rdi, QWORD PTR [rsp]".to_string());
+    lines.push("    mov esi, 6".to_string());
+    lines.push(format!("    mov edx, {}", STDOUT));
+    lines.push(format!("    call {}", WRITE_DIGITS));
+    lines.push("    jmp .Lprint_float_end".to_string());
+    lines.push(".Lprint_float_nan:".to_string());
+    lines.push("    lea rdi, [rip + .Lruntime_nan]".to_string());
+    lines.push(format!("    call {}", PRINT_STRING));
+    lines.push("    jmp .Lprint_float_end".to_string());
+    lines.push(".Lprint_float_inf:".to_string());
+    lines.push("    lea rdi, [rip + .Lruntime_inf]".to_string());
+    lines.push(format!("    call {}", PRINT_STRING));
+    lines.push(".Lprint_float_end:".to_string());
+    lines.push("    add rsp, 24".to_string());
+    lines.push("    ret".to_string());
+}
+
+// The strings the runtime writes, by the name of their label.
+const RUNTIME_STRINGS: [(&str, &str); 11] = [
+    ("true", "true"),
+    ("false", "false"),
+    ("nan", "nan"),
+    ("inf", "inf"),
+    ("minus", "-"),
+    ("out_of_bounds", "error: Index "),
+    ("out_of_bounds_len", " is out of bounds for length "),
+    ("out_of_bounds_end", ".\n"),
+    ("empty_pop", "error: Cannot pop from an empty list.\n"),
+    ("div_by_zero", "error: Division by zero.\n"),
+    ("out_of_memory", "error: Out of memory.\n"),
+];
+
+// Writes one of the strings of the runtime to a file descriptor.
+fn write_string(lines: &mut Vec<String>, name: &str, fd: &str) {
+    lines.push(format!("    lea rsi, [rip + .Lruntime_{}]", name));
+    lines.push("    mov rdx, QWORD PTR [rsi]".to_string());
+    lines.push("    add rsi, 8".to_string());
+    write(lines, fd);
+}
+
+// out_of_bounds(index, len) aborts the program when it indexes an array out of its bounds, after
+// writing the index and the length of the array to the standard error. It never returns, so it
+// keeps them in callee-saved registers without restoring them.
+fn generate_out_of_bounds(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", OUT_OF_BOUNDS));
+    lines.push("    sub rsp, 8".to_string());
+    lines.push("    mov rbx, rdi".to_string());
+    lines.push("    mov r12, rsi".to_string());
+    write_string(lines, "out_of_bounds", STDERR);
+    lines.push("    test rbx, rbx".to_string());
+    lines.push("    jns .Lout_of_bounds_positive".to_string());
+    write_string(lines, "minus", STDERR);
+    lines.push("    neg rbx".to_string());
+    lines.push(".Lout_of_bounds_positive:".to_string());
+    lines.push("    mov rdi, rbx".to_string());
+    lines.push("    mov esi, 1".to_string());
+    lines.push(format!("    mov edx, {}", STDERR));
+    lines.push(format!("    call {}", WRITE_DIGITS));
+    write_string(lines, "out_of_bounds_len", STDERR);
+    lines.push("    mov rdi, r12".to_string());
+    lines.push("    mov esi, 1".to_string());
+    lines.push(format!("    mov edx, {}", STDERR));
+    lines.push(format!("    call {}", WRITE_DIGITS));
+    write_string(lines, "out_of_bounds_end", STDERR);
+    lines.push(format!("    mov edi, {}", OUT_OF_BOUNDS_EXIT_CODE));
+    lines.push("    mov eax, 60".to_string());
+    lines.push("    syscall".to_string());
+}
+
+// div_by_zero() aborts the program when it divides an int by zero.
+fn generate_div_by_zero(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", DIV_BY_ZERO));
+    write_string(lines, "div_by_zero", STDERR);
+    lines.push(format!("    mov edi, {}", DIV_BY_ZERO_EXIT_CODE));
+    lines.push("    mov eax, 60".to_string());
+    lines.push("    syscall".to_string());
+}
+
+// list_new() returns a new list, which has no elements and no room for any. A list is its length,
+// its capacity and the address of its elements (8 bytes each).
+fn generate_list_new(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", LIST_NEW));
+    lines.push("    sub rsp, 8".to_string());
+    lines.push("    mov edi, 24".to_string());
+    lines.push(format!("    call {}", ALLOC));
+    lines.push("    mov QWORD PTR [rax], 0".to_string());
+    lines.push("    mov QWORD PTR [rax + 8], 0".to_string());
+    lines.push("    mov QWORD PTR [rax + 16], 0".to_string());
+    lines.push("    add rsp, 8".to_string());
+    lines.push("    ret".to_string());
+}
+
+// list_push(list, size) adds an element of size bytes at the end of a list, and returns its
+// address, so the caller can store the value. When the list runs out of room, its elements are
+// copied to new memory with twice the room (the old memory is not freed).
+fn generate_list_push(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", LIST_PUSH));
+    lines.push("    mov rax, QWORD PTR [rdi]".to_string());
+    lines.push("    cmp rax, QWORD PTR [rdi + 8]".to_string());
+    lines.push("    jb .Llist_push_room".to_string());
+    lines.push("    mov rax, QWORD PTR [rdi + 8]".to_string());
+    lines.push("    add rax, rax".to_string());
+    lines.push(format!("    mov ecx, {}", LIST_CAPACITY));
+    lines.push("    cmp rax, rcx".to_string());
+    lines.push("    cmovb rax, rcx".to_string());
+    lines.push("    mov QWORD PTR [rdi + 8], rax".to_string());
+    // The list and the size are kept on the stack while alloc is called, which also keeps rsp
+    // aligned.
+    lines.push("    push rdi".to_string());
+    lines.push("    push rsi".to_string());
+    lines.push("    sub rsp, 8".to_string());
+    lines.push("    mov rdi, rax".to_string());
+    lines.push("    imul rdi, rsi".to_string());
+    lines.push(format!("    call {}", ALLOC));
+    lines.push("    add rsp, 8".to_string());
+    lines.push("    pop r8".to_string());
+    lines.push("    pop rdx".to_string());
+    // REP MOVSB copies rcx bytes from rsi to rdi.
+    lines.push("    mov rcx, QWORD PTR [rdx]".to_string());
+    lines.push("    imul rcx, r8".to_string());
+    lines.push("    mov rsi, QWORD PTR [rdx + 16]".to_string());
+    lines.push("    mov rdi, rax".to_string());
+    lines.push("    rep movsb".to_string());
+    lines.push("    mov QWORD PTR [rdx + 16], rax".to_string());
+    lines.push("    mov rdi, rdx".to_string());
+    lines.push("    mov rsi, r8".to_string());
+    lines.push(".Llist_push_room:".to_string());
+    lines.push("    mov rax, QWORD PTR [rdi]".to_string());
+    lines.push("    imul rax, rsi".to_string());
+    lines.push("    add rax, QWORD PTR [rdi + 16]".to_string());
+    lines.push("    inc QWORD PTR [rdi]".to_string());
+    lines.push("    ret".to_string());
+}
+
+// list_pop(list, size) removes the last element (of size bytes) of a list, and returns its
+// address, so the caller can load the value. Popping from an empty list aborts the program, like
+// indexing it out of its bounds.
+fn generate_list_pop(lines: &mut Vec<String>) {
+    lines.push(format!("{}:", LIST_POP));
+    lines.push("    mov rax, QWORD PTR [rdi]".to_string());
+    lines.push("    test rax, rax".to_string());
+    lines.push("    jz .Llist_pop_empty".to_string());
+    lines.push("    dec rax".to_string());
+    lines.push("    mov QWORD PTR [rdi], rax".to_string());
+    lines.push("    imul rax, rsi".to_string());
+    lines.push("    add rax, QWORD PTR [rdi + 16]".to_string());
+    lines.push("    ret".to_string());
+    lines.push(".Llist_pop_empty:".to_string());
+    write_string(lines, "empty_pop", STDERR);
+    lines.push(format!("    mov edi, {}", OUT_OF_BOUNDS_EXIT_CODE));
+    lines.push("    mov eax, 60".to_string());
+    lines.push("    syscall".to_string());
+}
@@ -3,22 +3,36 @@ use std::env::consts::OS;
 use std::env::var;
 
 use super::parser::ast::*;
+use super::semantic::semantic::SymbolTable;
+mod aarch64;
+mod frame;
+mod layout;
 mod macos {
     pub mod aarch64;
 }
+mod linux {
+    pub mod aarch64;
+    pub mod x86_64;
+}
 
+// Code generators only get programs that passed the semantic analysis, so they cannot fail.
 pub trait CodeGenerator {
-    fn generate(&mut self, prog: &NodeProg) -> String;
+    fn generate(&mut self, prog: &NodeProg, symbols: SymbolTable) -> String;
 }
 
-pub fn code_generator_factory() -> Box<dyn CodeGenerator> {
+pub fn code_generator_factory() -> Result<Box<dyn CodeGenerator>, String> {
     let os = var("TARGET_OS").unwrap_or(OS.to_string());
     let arch = var("TARGET_ARCH").unwrap_or(ARCH.to_string());
     match os.as_str() {
         "macos" => match arch.as_str() {
-            "aarch64" => Box::new(macos::aarch64::MacOsAarch64::new()),
-            _ => panic!("UNSUPPORTED ARCHITECTURE: {}", arch),
+            "aarch64" => Ok(Box::new(macos::aarch64::MacOsAarch64::new())),
+            _ => Err(format!("UNSUPPORTED ARCHITECTURE: {}", arch)),
+        },
+        "linux" => match arch.as_str() {
+            "aarch64" => Ok(Box::new(linux::aarch64::LinuxAarch64::new())),
+            "x86_64" => Ok(Box::new(linux::x86_64::LinuxX86_64::new())),
+            _ => Err(format!("UNSUPPORTED ARCHITECTURE: {}", arch)),
         },
-        _ => panic!("UNSUPPORTED OPERATING SYSTEM: {}", os),
+        _ => Err(format!("UNSUPPORTED OPERATING SYSTEM: {}", os)),
     }
 }
@@ -0,0 +1,146 @@
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result;
+
+use super::lexer::tokens::Span;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+}
+
+// ANSI styles used when rendering diagnostics in color.
+const BOLD: &str = "1";
+const BLUE: &str = "1;34";
+
+impl Severity {
+    fn style(&self) -> &'static str {
+        match self {
+            Severity::Error => "1;31",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+// A diagnostic is a message about the program being compiled (as opposed to a bug in the
+// compiler itself). The span points at the part of the source code the message is about, and the
+// notes and help lines are extra information shown below the source code.
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(message: String, span: Span) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            span,
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn with_help(mut self, help: String) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    // Renders the diagnostic the same way rustc does, for example:
+    //
+    //     error: Variable my_vra not found.
+    //      --> ex/errors/2.ob:7:12
+    //       |
+    //     7 |     return my_vra;
+    //       |            ^^^^^^
+    //       = help: declare it before using it, e.g. `my_vra: int = 0;`
+    //
+    // The ANSI color codes are only added when color is true, so logs that are not shown on a
+    // terminal stay readable.
+    pub fn render(&self, fname: &str, source_code: &str, color: bool) -> String {
+        let paint = |style: &str, text: &str| {
+            if color {
+                format!("\x1b[{}m{}\x1b[0m", style, text)
+            } else {
+                text.to_string()
+            }
+        };
+        let (line, column) = line_and_column(source_code, self.span.start);
+        let line_start = source_code[..self.span.start]
+            .rfind('\n')
+            .map_or(0, |idx| idx + 1);
+        let line_text = source_code[line_start..].lines().next().unwrap_or("");
+
+        // Tabs are kept in the padding so the carets line up the same way the source line does.
+        let padding: String = source_code[line_start..self.span.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let span_end = self.span.end.min(line_start + line_text.len());
+        let width = source_code[self.span.start.min(span_end)..span_end]
+            .chars()
+            .count()
+            .max(1);
+
+        let line_number = line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let bar = paint(BLUE, "|");
+        let mut lines = vec![
+            format!(
+                "{}: {}",
+                paint(self.severity.style(), &self.severity.to_string()),
+                paint(BOLD, &self.message)
+            ),
+            format!(
+                "{}{} {}:{}:{}",
+                gutter,
+                paint(BLUE, "-->"),
+                fname,
+                line,
+                column
+            ),
+            format!("{} {}", gutter, bar),
+            format!("{} {} {}", paint(BLUE, &line_number), bar, line_text),
+            format!(
+                "{} {} {}{}",
+                gutter,
+                bar,
+                padding,
+                paint(self.severity.style(), &"^".repeat(width))
+            ),
+        ];
+        for note in self.notes.iter() {
+            lines.push(format!("{} {} note: {}", gutter, paint(BLUE, "="), note));
+        }
+        if let Some(help) = &self.help {
+            lines.push(format!("{} {} help: {}", gutter, paint(BLUE, "="), help));
+        }
+        lines.join("\n")
+    }
+}
+
+// Finds the line and column (both starting at 1) of a byte offset into the source code.
+// Columns are counted in characters, the same way the lexer counts them for the tokens.
+pub fn line_and_column(source_code: &str, offset: usize) -> (u32, u32) {
+    let before = &source_code[..offset];
+    let line = before.matches('\n').count() as u32 + 1;
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    let column = before[line_start..].chars().count() as u32 + 1;
+    (line, column)
+}
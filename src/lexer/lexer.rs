@@ -1,29 +1,32 @@
 use std::iter::Peekable;
-use std::str::Chars;
+use std::str::CharIndices;
 
+use super::super::diagnostic::Diagnostic;
 use super::tokens::get_keywords;
 use super::tokens::get_symbols;
+use super::tokens::Span;
 use super::tokens::Token;
 use super::tokens::TokenType;
 
-pub fn get_tokens(source_code: String) -> Vec<Token> {
-    let mut chars = source_code.chars().peekable();
+pub fn get_tokens(source_code: String) -> Result<Vec<Token>, Vec<Diagnostic>> {
+    let mut chars = source_code.char_indices().peekable();
     let mut line = 1;
     let mut column = 1;
 
     let symbols = get_symbols();
     let mut tokens: Vec<Token> = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
 
-    while let Some(c) = chars.next() {
+    while let Some((start, c)) = chars.next() {
         // Whitespace and comments are skipped completely
         if c == '\n' {
             line += 1;
             column = 1;
         } else if c.is_whitespace() {
             column += 1;
-        } else if c == '/' && chars.clone().next() == Some('/') {
+        } else if c == '/' && next_char(&chars) == Some('/') {
             let mut found_newline = false;
-            while let Some(c) = chars.next() {
+            for (_, c) in chars.by_ref() {
                 if c == '\n' {
                     line += 1;
                     column = 1;
@@ -34,10 +37,12 @@ pub fn get_tokens(source_code: String) -> Vec<Token> {
             if !found_newline {
                 break;
             }
-        } else if c == '/' && chars.clone().next() == Some('*') {
+        } else if c == '/' && next_char(&chars) == Some('*') {
+            chars.next();
+            column += 2;
             let mut found_end = false;
-            while let Some(c) = chars.next() {
-                if c == '*' && chars.clone().next() == Some('/') {
+            while let Some((_, c)) = chars.next() {
+                if c == '*' && next_char(&chars) == Some('/') {
                     chars.next();
                     column += 2;
                     found_end = true;
@@ -50,37 +55,70 @@ pub fn get_tokens(source_code: String) -> Vec<Token> {
                 }
             }
             if !found_end {
+                diagnostics.push(
+                    Diagnostic::error(
+                        "Unterminated block comment.".to_string(),
+                        Span::new(start, start + 2),
+                    )
+                    .with_help("close the comment with `*/`".to_string()),
+                );
                 break;
             }
 
         // Keywords and identifiers
         } else if c.is_alphabetic() || c == '_' {
-            tokens.push(keyword_or_id(&mut chars, &line, &mut column, c));
+            tokens.push(keyword_or_id(&mut chars, &line, &mut column, start, c));
 
-        // Integer Literals
-        } else if c.is_numeric() {
-            tokens.push(int_lit(&mut chars, &line, &mut column, c));
+        // Integer and Float Literals
+        } else if c.is_ascii_digit() {
+            tokens.push(number_lit(&mut chars, &line, &mut column, start, c));
+
+        // Character Literals
+        } else if c == '\'' {
+            match char_lit(&mut chars, &line, &mut column, start) {
+                Ok(token) => tokens.push(token),
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+
+        // String Literals
+        } else if c == '"' {
+            match str_lit(&mut chars, &line, &mut column, start) {
+                Ok(token) => tokens.push(token),
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
 
         // Symbols
-        } else if symbols.keys().any(|symbol| symbol == &c.to_string()) {
-            let value = c.to_string();
+        } else if let Some(value) = match_symbol(&chars, c) {
             let token_type = *symbols.get(value.as_str()).unwrap();
+            // The first character was already consumed, the rest of the symbol still has to be.
+            for _ in 1..value.len() {
+                chars.next();
+            }
+            let symbol_len = value.len() as u32;
             tokens.push(Token {
                 token_type,
+                span: Span::new(start, start + value.len()),
                 value,
                 line,
                 column,
             });
-            column += 1;
+            column += symbol_len;
 
         // Unknown token found
         } else {
+            let span = Span::new(start, start + c.len_utf8());
+            diagnostics.push(
+                Diagnostic::error(format!("Unknown character {:?}.", c), span)
+                    .with_help("remove this character".to_string()),
+            );
             tokens.push(Token {
                 token_type: TokenType::UNKNOWN,
                 value: c.to_string(),
                 line,
                 column,
+                span,
             });
+            column += 1;
         }
     }
 
@@ -89,23 +127,47 @@ pub fn get_tokens(source_code: String) -> Vec<Token> {
         value: String::from("<EOF>"),
         line,
         column,
+        span: Span::new(source_code.len(), source_code.len()),
     });
-    return tokens;
+    if diagnostics.is_empty() {
+        Ok(tokens)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn next_char(chars: &Peekable<CharIndices>) -> Option<char> {
+    chars.clone().next().map(|(_, c)| c)
+}
+
+// Finds the symbol that starts with the given character. Two character symbols are preferred, so
+// `<=` is lexed as a single symbol instead of `<` followed by `=`.
+fn match_symbol(chars: &Peekable<CharIndices>, first_char: char) -> Option<String> {
+    let symbols = get_symbols();
+    if let Some(second_char) = next_char(chars) {
+        let value = format!("{}{}", first_char, second_char);
+        if symbols.contains_key(value.as_str()) {
+            return Some(value);
+        }
+    }
+    let value = first_char.to_string();
+    symbols.contains_key(value.as_str()).then_some(value)
 }
 
 fn keyword_or_id(
-    chars: &mut Peekable<Chars>,
+    chars: &mut Peekable<CharIndices>,
     line_start: &u32,
     column_start: &mut u32,
+    start: usize,
     first_char: char,
 ) -> Token {
     let mut value = first_char.to_string();
     let line = *line_start;
     let column = *column_start;
-    while let Some(c) = chars.peek() {
+    while let Some((_, c)) = chars.peek() {
         *column_start += 1;
         if c.is_alphanumeric() || c == &'_' {
-            value.push(chars.next().unwrap());
+            value.push(chars.next().unwrap().1);
         } else {
             break;
         }
@@ -117,33 +179,221 @@ fn keyword_or_id(
     };
     Token {
         token_type,
+        span: Span::new(start, start + value.len()),
         value,
         line,
         column,
     }
 }
 
-fn int_lit(
-    chars: &mut Peekable<Chars>,
+// Lexes an integer literal (`42`) or a float literal. Floats have a fraction (`3.14`), an exponent
+// (`1e9`, `2.5E-3`) or both. There has to be a digit after the dot, and after the exponent sign.
+fn number_lit(
+    chars: &mut Peekable<CharIndices>,
     line_start: &u32,
     column_start: &mut u32,
+    start: usize,
     first_char: char,
 ) -> Token {
     let mut value = first_char.to_string();
     let line = *line_start;
     let column = *column_start;
-    while let Some(c) = chars.peek() {
-        *column_start += 1;
-        if c.is_numeric() {
-            value.push(chars.next().unwrap());
-        } else {
-            break;
+    let mut token_type = TokenType::IntLit;
+    take_digits(chars, &mut value);
+    let mut lookahead = chars.clone();
+    if lookahead.next().map(|(_, c)| c) == Some('.')
+        && lookahead.next().is_some_and(|(_, c)| c.is_ascii_digit())
+    {
+        value.push(chars.next().unwrap().1);
+        take_digits(chars, &mut value);
+        token_type = TokenType::FloatLit;
+    }
+    let mut lookahead = chars.clone();
+    if matches!(lookahead.next(), Some((_, 'e' | 'E'))) {
+        let mut exponent_len = 1;
+        if matches!(lookahead.peek(), Some((_, '+' | '-'))) {
+            lookahead.next();
+            exponent_len += 1;
+        }
+        if lookahead.next().is_some_and(|(_, c)| c.is_ascii_digit()) {
+            for _ in 0..exponent_len {
+                value.push(chars.next().unwrap().1);
+            }
+            take_digits(chars, &mut value);
+            token_type = TokenType::FloatLit;
         }
     }
+    // Numbers are ASCII, so every byte is a column.
+    *column_start += value.len() as u32;
     Token {
-        token_type: TokenType::IntLit,
+        token_type,
+        span: Span::new(start, start + value.len()),
+        value,
+        line,
+        column,
+    }
+}
+
+fn take_digits(chars: &mut Peekable<CharIndices>, value: &mut String) {
+    while let Some((_, c)) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        value.push(chars.next().unwrap().1);
+    }
+}
+
+// Lexes a character literal, whose opening quote was already consumed. The value of the token is
+// the character itself (escapes are already replaced), which has to fit in 8 bits.
+fn char_lit(
+    chars: &mut Peekable<CharIndices>,
+    line_start: &u32,
+    column_start: &mut u32,
+    start: usize,
+) -> Result<Token, Diagnostic> {
+    let line = *line_start;
+    let column = *column_start;
+    // Everything up to the closing quote is consumed (but never a newline), so the rest of a broken
+    // literal is not lexed as more tokens.
+    let mut content: Vec<(usize, char)> = Vec::new();
+    let mut end = None;
+    while let Some(&(idx, c)) = chars.peek() {
+        if c == '\n' {
+            break;
+        }
+        chars.next();
+        // A quote right after a backslash is the `\'` escape, not the end of the literal.
+        let escaped = matches!(content.as_slice(), [(_, '\\')]);
+        if c == '\'' && !escaped {
+            end = Some(idx + 1);
+            break;
+        }
+        content.push((idx, c));
+    }
+    *column_start += 1 + content.len() as u32 + end.is_some() as u32;
+    let Some(end) = end else {
+        return Err(Diagnostic::error(
+            "Unterminated character literal.".to_string(),
+            Span::new(start, start + 1),
+        )
+        .with_help("close the character literal with `'`".to_string()));
+    };
+    let span = Span::new(start, end);
+    let value = match content.as_slice() {
+        [] => {
+            return Err(
+                Diagnostic::error("Empty character literal.".to_string(), span)
+                    .with_help("put a character between the quotes, e.g. `'a'`".to_string()),
+            )
+        }
+        [(_, '\\'), (idx, c)] => escape(*c, *idx)?,
+        [(_, c)] if (*c as u32) <= 0xff => *c,
+        [(_, c)] => {
+            return Err(Diagnostic::error(
+                format!("Character {:?} does not fit in 8 bits.", c),
+                span,
+            ))
+        }
+        _ => {
+            return Err(Diagnostic::error(
+                "Character literals can only contain one character.".to_string(),
+                span,
+            ))
+        }
+    };
+    Ok(Token {
+        token_type: TokenType::CharLit,
+        value: value.to_string(),
+        line,
+        column,
+        span,
+    })
+}
+
+// Lexes a string literal, whose opening quote was already consumed. The value of the token is the
+// content of the string (escapes are already replaced). Unlike a char, a string can hold any
+// character, since it is stored as UTF-8.
+fn str_lit(
+    chars: &mut Peekable<CharIndices>,
+    line_start: &u32,
+    column_start: &mut u32,
+    start: usize,
+) -> Result<Token, Diagnostic> {
+    let line = *line_start;
+    let column = *column_start;
+    // Everything up to the closing quote is consumed (but never a newline), so the rest of a broken
+    // literal is not lexed as more tokens. Only the first bad escape is reported.
+    let mut value = String::new();
+    let mut error = None;
+    let mut end = None;
+    let mut length = 1;
+    while let Some(&(idx, c)) = chars.peek() {
+        if c == '\n' {
+            break;
+        }
+        chars.next();
+        length += 1;
+        if c == '"' {
+            end = Some(idx + 1);
+            break;
+        }
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        let Some(&(escape_idx, escaped)) = chars.peek() else {
+            break;
+        };
+        if escaped == '\n' {
+            break;
+        }
+        chars.next();
+        length += 1;
+        match escape(escaped, escape_idx) {
+            Ok(escaped) => value.push(escaped),
+            Err(diagnostic) => {
+                error.get_or_insert(diagnostic);
+            }
+        }
+    }
+    *column_start += length;
+    let Some(end) = end else {
+        return Err(Diagnostic::error(
+            "Unterminated string literal.".to_string(),
+            Span::new(start, start + 1),
+        )
+        .with_help("close the string literal with `\"`".to_string()));
+    };
+    if let Some(diagnostic) = error {
+        return Err(diagnostic);
+    }
+    Ok(Token {
+        token_type: TokenType::StrLit,
         value,
         line,
         column,
+        span: Span::new(start, end),
+    })
+}
+
+// Replaces an escape sequence (the character after a backslash, at byte offset idx) in a char or
+// string literal with the character it stands for.
+fn escape(escape: char, idx: usize) -> Result<char, Diagnostic> {
+    match escape {
+        'n' => Ok('\n'),
+        't' => Ok('\t'),
+        'r' => Ok('\r'),
+        '0' => Ok('\0'),
+        '\\' => Ok('\\'),
+        '\'' => Ok('\''),
+        '"' => Ok('"'),
+        _ => Err(Diagnostic::error(
+            format!("Unknown escape sequence `\\{}`.", escape),
+            Span::new(idx - 1, idx + escape.len_utf8()),
+        )
+        .with_help(
+            "the supported escapes are `\\n`, `\\t`, `\\r`, `\\0`, `\\\\`, `\\'` and `\\\"`"
+                .to_string(),
+        )),
     }
 }
@@ -4,34 +4,104 @@ use std::fmt::Formatter;
 use std::fmt::Result;
 use std::sync::OnceLock;
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TokenType {
     // Keywords
     //// Types
     Int,
+    Float,
+    Bool,
+    Char,
+    String,
     //// Control flow
     Ret,
+    If,
+    Else,
+    While,
+    Break,
+    Continue,
+    //// Modifiers
+    Const,
+    Pub,
+    Prv,
+    //// Classes
+    Class,
+    This,
+    Super,
+    //// Enums
+    Enum,
     // Symbols
     LP,
     RP,
     LB,
     RB,
+    LSB,
+    RSB,
     Semi,
     Colon,
+    Comma,
+    Dot,
     Assign,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Tilde,
+    Not,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
     // Complex Tokens
     Id,
     IntLit,
+    FloatLit,
+    CharLit,
+    StrLit,
+    // `true` and `false` are keywords, but they are lexed as literals
+    BoolLit,
     // Special Tokens
     EOF,
     UNKNOWN,
 }
 
+// A span is a range of byte offsets into the source code. The start is inclusive and the end is
+// exclusive, so the text of a span is &source_code[span.start..span.end].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    // Creates a span that starts where this one starts and ends where the other one ends.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+}
+
+impl Debug for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
 pub struct Token {
     pub token_type: TokenType,
     pub value: String,
     pub line: u32,
     pub column: u32,
+    pub span: Span,
 }
 
 impl Debug for Token {
@@ -53,7 +123,25 @@ static KEYWORDS: OnceLock<HashMap<&'static str, TokenType>> = OnceLock::new();
 fn init_keywords() -> HashMap<&'static str, TokenType> {
     let mut keywords: HashMap<&'static str, TokenType> = HashMap::new();
     keywords.insert("int", TokenType::Int);
+    keywords.insert("float", TokenType::Float);
+    keywords.insert("bool", TokenType::Bool);
+    keywords.insert("char", TokenType::Char);
+    keywords.insert("string", TokenType::String);
+    keywords.insert("true", TokenType::BoolLit);
+    keywords.insert("false", TokenType::BoolLit);
     keywords.insert("return", TokenType::Ret);
+    keywords.insert("if", TokenType::If);
+    keywords.insert("else", TokenType::Else);
+    keywords.insert("while", TokenType::While);
+    keywords.insert("break", TokenType::Break);
+    keywords.insert("continue", TokenType::Continue);
+    keywords.insert("const", TokenType::Const);
+    keywords.insert("pub", TokenType::Pub);
+    keywords.insert("prv", TokenType::Prv);
+    keywords.insert("class", TokenType::Class);
+    keywords.insert("this", TokenType::This);
+    keywords.insert("super", TokenType::Super);
+    keywords.insert("enum", TokenType::Enum);
     keywords
 }
 pub fn get_keywords() -> &'static HashMap<&'static str, TokenType> {
@@ -67,9 +155,28 @@ fn init_symbols() -> HashMap<&'static str, TokenType> {
     symbols.insert(")", TokenType::RP);
     symbols.insert("{", TokenType::LB);
     symbols.insert("}", TokenType::RB);
+    symbols.insert("[", TokenType::LSB);
+    symbols.insert("]", TokenType::RSB);
     symbols.insert(";", TokenType::Semi);
     symbols.insert(":", TokenType::Colon);
+    symbols.insert(",", TokenType::Comma);
+    symbols.insert(".", TokenType::Dot);
     symbols.insert("=", TokenType::Assign);
+    symbols.insert("+", TokenType::Plus);
+    symbols.insert("-", TokenType::Minus);
+    symbols.insert("*", TokenType::Star);
+    symbols.insert("/", TokenType::Slash);
+    symbols.insert("%", TokenType::Percent);
+    symbols.insert("~", TokenType::Tilde);
+    symbols.insert("!", TokenType::Not);
+    symbols.insert("==", TokenType::Eq);
+    symbols.insert("!=", TokenType::Ne);
+    symbols.insert("<", TokenType::Lt);
+    symbols.insert("<=", TokenType::Le);
+    symbols.insert(">", TokenType::Gt);
+    symbols.insert(">=", TokenType::Ge);
+    symbols.insert("&&", TokenType::And);
+    symbols.insert("||", TokenType::Or);
     symbols
 }
 pub fn get_symbols() -> &'static HashMap<&'static str, TokenType> {
@@ -1,20 +1,64 @@
 use std::env::args;
+use std::env::var;
 use std::fs::read_to_string;
 use std::fs::remove_file;
 use std::fs::write;
+use std::io::stderr;
+use std::io::IsTerminal;
+use std::process::exit;
 use std::process::Command;
 
 mod lexer {
+    #[allow(clippy::module_inception)]
     pub mod lexer;
     pub mod tokens;
 }
 
 mod parser {
     pub mod ast;
+    #[allow(clippy::module_inception)]
     pub mod parser;
 }
 
+mod semantic {
+    pub mod mangle;
+    #[allow(clippy::module_inception)]
+    pub mod semantic;
+}
+
 mod code_generator;
+mod diagnostic;
+
+use diagnostic::Diagnostic;
+use semantic::mangle::demangle_message;
+
+// Reports an error that is not about the program being compiled, and stops the compiler.
+fn exit_with_error(message: String) -> ! {
+    eprintln!("error: {}", message);
+    exit(1);
+}
+
+// Reports every diagnostic found in the program being compiled, and stops the compiler.
+fn exit_with_diagnostics(fname: &str, src: &str, color: bool, diagnostics: Vec<Diagnostic>) -> ! {
+    for diagnostic in diagnostics.iter() {
+        eprintln!("{}\n", diagnostic.render(fname, src, color));
+    }
+    exit(1);
+}
+
+// Runs an external tool (the assembler or the linker) and stops the compiler if it fails. The
+// symbols in its errors are demangled, since they are not the names used in the program.
+fn run_tool(command: &mut Command, name: &str) {
+    match command.output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => exit_with_error(format!(
+            "{} failed:\n{}",
+            name,
+            demangle_message(&String::from_utf8_lossy(&output.stderr))
+        )),
+        Err(err) => exit_with_error(format!("Failed to run {}: {}", name, err)),
+    }
+}
 
 fn main() {
     // The command to run this compiler will have the following format:
@@ -25,6 +69,8 @@ fn main() {
     // The second argument is the option to print the tokens generated by the lexer
     // The third argument is the option to print the AST generated by the parser
     // The fourth argument is the option to print the assembly code generated by the code generator
+    // The --no-build option stops after generating the assembly code (useful for cross targets)
+    // The --color=always|never|auto option controls whether diagnostics are colored (default auto)
     // All the arguments are optional except for the file name, and they can be in any order
 
     // Firstly, we will get the arguments passed to the program
@@ -34,54 +80,73 @@ fn main() {
     let mut print_ast = false;
     let mut print_asm = false;
     let mut store_asm = false;
+    let mut build = true;
+    // By default, diagnostics are only colored when they are shown on a terminal
+    let mut color = stderr().is_terminal() && var("NO_COLOR").is_err();
     for arg in args.iter().skip(1) {
         if arg.starts_with("--") {
             match arg.as_str() {
                 "--ast" => print_ast = true,
                 "--asm" => print_asm = true,
-                _ => panic!("Unknown option {}", arg),
+                "--no-build" => build = false,
+                "--color=always" => color = true,
+                "--color=never" => color = false,
+                "--color=auto" => {}
+                _ => exit_with_error(format!("Unknown option {}", arg)),
             }
         } else if arg.starts_with('-') && arg.len() > 1 {
             for c in arg.chars().skip(1) {
                 match c {
                     't' => print_tokens = true,
                     's' => store_asm = true,
-                    _ => panic!("Unknown option {}", c),
+                    _ => exit_with_error(format!("Unknown option -{}", c)),
                 }
             }
         } else {
-            if fname != "" {
-                panic!("Multiple file names provided, {} and {}", fname, arg);
+            if !fname.is_empty() {
+                exit_with_error(format!(
+                    "Multiple file names provided, {} and {}",
+                    fname, arg
+                ));
             }
             fname = arg;
         }
     }
-    if fname == "" {
-        panic!("No file name provided");
+    if fname.is_empty() {
+        exit_with_error("No file name provided".to_string());
     }
 
     let fname_no_ext = fname.split('.').collect::<Vec<&str>>()[0];
 
     // Now we will read the file and pass it to the lexer
-    let src = read_to_string(&fname).expect(format!("Could not read file {}", fname).as_str());
-    let tokens = lexer::lexer::get_tokens(src);
+    let src = read_to_string(fname)
+        .unwrap_or_else(|err| exit_with_error(format!("Could not read file {}: {}", fname, err)));
+    let tokens = lexer::lexer::get_tokens(src.clone())
+        .unwrap_or_else(|diagnostics| exit_with_diagnostics(fname, &src, color, diagnostics));
     // Print the tokens if the option is enabled
     if print_tokens {
         println!("{:?}", tokens);
     }
 
     // We will now pass the tokens to the parser
-    let ast = parser::parser::parse_prog(tokens);
+    let ast = parser::parser::parse_prog(tokens)
+        .unwrap_or_else(|diagnostics| exit_with_diagnostics(fname, &src, color, diagnostics));
     // Print the AST if the option is enabled
     if print_ast {
         println!("{:?}", ast);
     }
 
+    // Before generating any code, we will check that the program makes sense. This resolves every
+    // identifier to what it refers to, which the code generator relies on.
+    let symbols = semantic::semantic::analyze(&ast)
+        .unwrap_or_else(|diagnostics| exit_with_diagnostics(fname, &src, color, diagnostics));
+
     // Now we will use the AST to generate assembly code.
     // However, we need to know the target platform to generate the correct code.
     // We will use the code_generator_factory function to create the correct code generator.
-    let mut generator = code_generator::code_generator_factory();
-    let asm = generator.generate(&ast);
+    let mut generator =
+        code_generator::code_generator_factory().unwrap_or_else(|err| exit_with_error(err));
+    let asm = generator.generate(&ast, symbols);
     // Print the assembly code if the option is enabled
     if print_asm {
         println!("{}", asm);
@@ -90,26 +155,36 @@ fn main() {
     // Last step, we will create the executable
     if store_asm {
         let asm_fname = format!("{}.s", &fname_no_ext);
-        write(&asm_fname, &asm).expect(format!("Could not write file {}", &asm_fname).as_str());
+        write(&asm_fname, &asm).unwrap_or_else(|err| {
+            exit_with_error(format!("Could not write file {}: {}", &asm_fname, err))
+        });
+    }
+    if !build {
+        return;
     }
     // First step is to assemble the code
     let asm_fname = format!("{}_tmp.s", &fname_no_ext);
-    write(&asm_fname, &asm).expect(format!("Could not write file {}", &asm_fname).as_str());
+    write(&asm_fname, &asm).unwrap_or_else(|err| {
+        exit_with_error(format!("Could not write file {}: {}", &asm_fname, err))
+    });
     let obj_fname = format!("{}_tmp.o", &fname_no_ext);
-    Command::new("as")
-        .arg("-o")
-        .arg(&obj_fname)
-        .arg(&asm_fname)
-        .output()
-        .expect("Failed to assemble the code");
+    run_tool(
+        Command::new("as").arg("-o").arg(&obj_fname).arg(&asm_fname),
+        "as",
+    );
     // Next, we will link the object file
-    Command::new("ld")
-        .arg("-o")
-        .arg(&fname_no_ext)
-        .arg(&obj_fname)
-        .output()
-        .expect("Failed to link the object file");
+    run_tool(
+        Command::new("ld")
+            .arg("-o")
+            .arg(fname_no_ext)
+            .arg(&obj_fname),
+        "ld",
+    );
     // Finally, we will remove the temporary files
-    remove_file(&asm_fname).expect("Failed to remove the temporary assembly file");
-    remove_file(&obj_fname).expect("Failed to remove the temporary object file");
+    remove_file(&asm_fname).unwrap_or_else(|err| {
+        exit_with_error(format!("Could not remove file {}: {}", &asm_fname, err))
+    });
+    remove_file(&obj_fname).unwrap_or_else(|err| {
+        exit_with_error(format!("Could not remove file {}: {}", &obj_fname, err))
+    });
 }
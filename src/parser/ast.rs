@@ -2,13 +2,26 @@ use std::fmt::Debug;
 use std::fmt::Formatter;
 use std::fmt::Result;
 
+pub use super::super::lexer::tokens::Span;
+
+// Every node keeps the span of the source code it was parsed from, so that later stages can point
+// at the right place when reporting diagnostics.
 pub struct NodeProg {
+    pub classes: Vec<NodeClass>,
+    pub enums: Vec<NodeEnum>,
     pub functions: Vec<NodeFunc>,
+    pub span: Span,
 }
 
 impl Debug for NodeProg {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "<Prog")?;
+        write!(f, "<Prog span={:?}", self.span)?;
+        for (idx, class) in self.classes.iter().enumerate() {
+            write!(f, " class_{}={:?}", idx, class)?;
+        }
+        for (idx, node_enum) in self.enums.iter().enumerate() {
+            write!(f, " enum_{}={:?}", idx, node_enum)?;
+        }
         for (idx, func) in self.functions.iter().enumerate() {
             write!(f, " func_{}={:?}", idx, func)?;
         }
@@ -16,29 +29,148 @@ impl Debug for NodeProg {
     }
 }
 
+pub struct NodeClass {
+    pub ident: NodeIdent,
+    // The class this one extends, if any.
+    pub base: Option<NodeIdent>,
+    pub fields: Vec<NodeField>,
+    pub constructors: Vec<NodeMethod>,
+    pub methods: Vec<NodeMethod>,
+    pub span: Span,
+}
+
+impl Debug for NodeClass {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "<Class {:?}", self.ident)?;
+        if let Some(base) = &self.base {
+            write!(f, " base=<{:?}>", base)?;
+        }
+        for (idx, field) in self.fields.iter().enumerate() {
+            write!(f, " field_{}={:?}", idx, field)?;
+        }
+        for (idx, constructor) in self.constructors.iter().enumerate() {
+            write!(f, " constructor_{}={:?}", idx, constructor)?;
+        }
+        for (idx, method) in self.methods.iter().enumerate() {
+            write!(f, " method_{}={:?}", idx, method)?;
+        }
+        write!(f, " span={:?}>", self.span)
+    }
+}
+
+// An enumeration, whose values are its variants. The variants are numbered from 0, in the order
+// they are declared.
+pub struct NodeEnum {
+    pub ident: NodeIdent,
+    pub variants: Vec<NodeIdent>,
+    pub span: Span,
+}
+
+impl Debug for NodeEnum {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "<Enum {:?}", self.ident)?;
+        for (idx, variant) in self.variants.iter().enumerate() {
+            write!(f, " variant_{}={:?}", idx, variant)?;
+        }
+        write!(f, " span={:?}>", self.span)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+impl Debug for Visibility {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Visibility::Public => write!(f, "pub"),
+            Visibility::Private => write!(f, "prv"),
+        }
+    }
+}
+
+pub struct NodeField {
+    pub visibility: Visibility,
+    pub ident: NodeIdent,
+    pub f_type: NodeType,
+    pub span: Span,
+}
+
+impl Debug for NodeField {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(
+            f,
+            "<Field {:?} visibility={:?} f_type={:?} span={:?}>",
+            self.ident, self.visibility, self.f_type, self.span
+        )
+    }
+}
+
+// A function that belongs to a class. Constructors are the ones with the name of their class.
+pub struct NodeMethod {
+    pub visibility: Visibility,
+    pub func: NodeFunc,
+    pub span: Span,
+}
+
+impl Debug for NodeMethod {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(
+            f,
+            "<Method visibility={:?} func={:?} span={:?}>",
+            self.visibility, self.func, self.span
+        )
+    }
+}
+
 pub struct NodeFunc {
     pub ident: NodeIdent,
+    pub params: Vec<NodeParam>,
     pub r_type: NodeType,
     pub block: NodeBlock,
+    pub span: Span,
 }
 
 impl Debug for NodeFunc {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "<Func {:?}", self.ident)?;
+        for (idx, param) in self.params.iter().enumerate() {
+            write!(f, " param_{}={:?}", idx, param)?;
+        }
+        write!(
+            f,
+            " r_type={:?} block={:?} span={:?}>",
+            self.r_type, self.block, self.span
+        )
+    }
+}
+
+pub struct NodeParam {
+    pub ident: NodeIdent,
+    pub p_type: NodeType,
+    pub span: Span,
+}
+
+impl Debug for NodeParam {
     fn fmt(&self, f: &mut Formatter) -> Result {
         write!(
             f,
-            "<Func {:?} r_type={:?} block={:?}>",
-            self.ident, self.r_type, self.block
+            "<Param {:?} p_type={:?} span={:?}>",
+            self.ident, self.p_type, self.span
         )
     }
 }
 
 pub struct NodeBlock {
     pub stmts: Vec<NodeStmt>,
+    pub span: Span,
 }
 
 impl Debug for NodeBlock {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "<Block")?;
+        write!(f, "<Block span={:?}", self.span)?;
         for (idx, stmt) in self.stmts.iter().enumerate() {
             write!(f, " stmt_{}={:?}", idx, stmt)?;
         }
@@ -47,91 +179,380 @@ impl Debug for NodeBlock {
 }
 
 pub enum NodeStmt {
-    Return(NodeExpr),
-    Assign(NodeIdent, NodeType, NodeExpr),
+    Return(NodeExpr, Span),
+    // A declaration. The bool is whether the variable is mutable (declared without `const`).
+    Assign(NodeIdent, NodeType, NodeExpr, bool, Span),
+    Reassign(NodeIdent, NodeExpr, Span),
+    // The condition, the block that runs when it is true and the (optional) else block.
+    If(NodeExpr, NodeBlock, Option<NodeBlock>, Span),
+    // The condition and the body of the loop.
+    While(NodeExpr, NodeBlock, Span),
+    Break(Span),
+    Continue(Span),
+    // Assigns to an element of an array: the array, the index and the value. The array is a
+    // variable, or an element of one (for arrays of arrays).
+    IndexAssign(NodeExpr, NodeExpr, NodeExpr, Span),
+    // Assigns to a field of an object: the object, the field and the value.
+    MemberAssign(NodeExpr, NodeIdent, NodeExpr, Span),
+    // An expression whose value is not used. Only calls can be used as statements.
+    Expr(NodeExpr, Span),
+}
+
+impl NodeStmt {
+    pub fn span(&self) -> Span {
+        match self {
+            NodeStmt::Return(_, span) => *span,
+            NodeStmt::Assign(_, _, _, _, span) => *span,
+            NodeStmt::Reassign(_, _, span) => *span,
+            NodeStmt::If(_, _, _, span) => *span,
+            NodeStmt::While(_, _, span) => *span,
+            NodeStmt::Break(span) => *span,
+            NodeStmt::Continue(span) => *span,
+            NodeStmt::IndexAssign(_, _, _, span) => *span,
+            NodeStmt::MemberAssign(_, _, _, span) => *span,
+            NodeStmt::Expr(_, span) => *span,
+        }
+    }
 }
 
 impl Debug for NodeStmt {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
-            NodeStmt::Return(expr) => write!(f, "<Return expr={:?}>", expr),
-            NodeStmt::Assign(ident, a_type, expr) => {
+            NodeStmt::Return(expr, span) => {
+                write!(f, "<Return expr={:?} span={:?}>", expr, span)
+            }
+            NodeStmt::Assign(ident, a_type, expr, mutable, span) => {
+                write!(
+                    f,
+                    "<Assign {:?} a_type={:?} expr={:?} mutable={} span={:?}>",
+                    ident, a_type, expr, mutable, span
+                )
+            }
+            NodeStmt::Reassign(ident, expr, span) => {
+                write!(f, "<Reassign {:?} expr={:?} span={:?}>", ident, expr, span)
+            }
+            NodeStmt::If(cond, then_block, else_block, span) => {
+                write!(f, "<If cond={:?} then={:?}", cond, then_block)?;
+                if let Some(else_block) = else_block {
+                    write!(f, " else={:?}", else_block)?;
+                }
+                write!(f, " span={:?}>", span)
+            }
+            NodeStmt::While(cond, body, span) => {
+                write!(f, "<While cond={:?} body={:?} span={:?}>", cond, body, span)
+            }
+            NodeStmt::Break(span) => write!(f, "<Break span={:?}>", span),
+            NodeStmt::Continue(span) => write!(f, "<Continue span={:?}>", span),
+            NodeStmt::IndexAssign(array, index, expr, span) => {
                 write!(
                     f,
-                    "<Assign {:?} a_type={:?} expr={:?}>",
-                    ident, a_type, expr
+                    "<IndexAssign array={:?} index={:?} expr={:?} span={:?}>",
+                    array, index, expr, span
                 )
             }
+            NodeStmt::MemberAssign(object, field, expr, span) => {
+                write!(
+                    f,
+                    "<MemberAssign object={:?} field={:?} expr={:?} span={:?}>",
+                    object, field, expr, span
+                )
+            }
+            NodeStmt::Expr(expr, span) => write!(f, "<Expr expr={:?} span={:?}>", expr, span),
         }
     }
 }
 
 pub enum NodeExpr {
-    Literal(NodeLiteral),
+    Literal(NodeLiteral, Span),
     Ident(NodeIdent),
+    Unary(UnaryOp, Box<NodeExpr>, Span),
+    Binary(BinaryOp, Box<NodeExpr>, Box<NodeExpr>, Span),
+    Paren(Box<NodeExpr>, Span),
+    Call(NodeIdent, Vec<NodeExpr>, Span),
+    // An array literal, with every element (`[1, 2, 3]`).
+    Array(Vec<NodeExpr>, Span),
+    // An array literal with the same value repeated a number of times (`[0; 5]`).
+    ArrayRepeat(Box<NodeExpr>, usize, Span),
+    // A new list without any elements (`[]`). Its type is the one it is expected to have.
+    EmptyList(Span),
+    // An element of an array (or a list): the array and the index.
+    Index(Box<NodeExpr>, Box<NodeExpr>, Span),
+    // The object a constructor is initializing, or a method is called on.
+    This(Span),
+    // A field of an object: the object and the field.
+    Member(Box<NodeExpr>, NodeIdent, Span),
+    // A call to a method of an object: the object, the method and the arguments.
+    MethodCall(Box<NodeExpr>, NodeIdent, Vec<NodeExpr>, Span),
+    // `this` seen as an object of the base class, which is only used to call its methods
+    // (`super.method(...)`) without dynamic dispatch.
+    Super(Span),
+    // A call to a constructor of the base class on `this` (`super(...)`): the `super` keyword and
+    // the arguments.
+    SuperCall(NodeIdent, Vec<NodeExpr>, Span),
+}
+
+impl NodeExpr {
+    pub fn span(&self) -> Span {
+        match self {
+            NodeExpr::Literal(_, span) => *span,
+            NodeExpr::Ident(ident) => ident.span,
+            NodeExpr::Unary(_, _, span) => *span,
+            NodeExpr::Binary(_, _, _, span) => *span,
+            NodeExpr::Paren(_, span) => *span,
+            NodeExpr::Call(_, _, span) => *span,
+            NodeExpr::Array(_, span) => *span,
+            NodeExpr::ArrayRepeat(_, _, span) => *span,
+            NodeExpr::EmptyList(span) => *span,
+            NodeExpr::Index(_, _, span) => *span,
+            NodeExpr::This(span) => *span,
+            NodeExpr::Member(_, _, span) => *span,
+            NodeExpr::MethodCall(_, _, _, span) => *span,
+            NodeExpr::Super(span) => *span,
+            NodeExpr::SuperCall(_, _, span) => *span,
+        }
+    }
 }
 
 impl Debug for NodeExpr {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
-            NodeExpr::Literal(literal) => write!(f, "<Literal {:?}>", literal),
+            NodeExpr::Literal(literal, span) => {
+                write!(f, "<Literal {:?} span={:?}>", literal, span)
+            }
             NodeExpr::Ident(ident) => write!(f, "<Ident {:?}>", ident),
+            NodeExpr::Unary(op, expr, span) => {
+                write!(f, "<Unary op={:?} expr={:?} span={:?}>", op, expr, span)
+            }
+            NodeExpr::Binary(op, lhs, rhs, span) => {
+                write!(
+                    f,
+                    "<Binary op={:?} lhs={:?} rhs={:?} span={:?}>",
+                    op, lhs, rhs, span
+                )
+            }
+            NodeExpr::Paren(expr, span) => write!(f, "<Paren expr={:?} span={:?}>", expr, span),
+            NodeExpr::Call(ident, args, span) => {
+                write!(f, "<Call {:?}", ident)?;
+                for (idx, arg) in args.iter().enumerate() {
+                    write!(f, " arg_{}={:?}", idx, arg)?;
+                }
+                write!(f, " span={:?}>", span)
+            }
+            NodeExpr::Array(elements, span) => {
+                write!(f, "<Array")?;
+                for (idx, element) in elements.iter().enumerate() {
+                    write!(f, " element_{}={:?}", idx, element)?;
+                }
+                write!(f, " span={:?}>", span)
+            }
+            NodeExpr::EmptyList(span) => write!(f, "<EmptyList span={:?}>", span),
+            NodeExpr::ArrayRepeat(value, count, span) => {
+                write!(
+                    f,
+                    "<ArrayRepeat value={:?} count={} span={:?}>",
+                    value, count, span
+                )
+            }
+            NodeExpr::Index(array, index, span) => {
+                write!(
+                    f,
+                    "<Index array={:?} index={:?} span={:?}>",
+                    array, index, span
+                )
+            }
+            NodeExpr::This(span) => write!(f, "<This span={:?}>", span),
+            NodeExpr::Member(object, field, span) => {
+                write!(
+                    f,
+                    "<Member object={:?} field={:?} span={:?}>",
+                    object, field, span
+                )
+            }
+            NodeExpr::MethodCall(object, method, args, span) => {
+                write!(f, "<MethodCall object={:?} method={:?}", object, method)?;
+                for (idx, arg) in args.iter().enumerate() {
+                    write!(f, " arg_{}={:?}", idx, arg)?;
+                }
+                write!(f, " span={:?}>", span)
+            }
+            NodeExpr::Super(span) => write!(f, "<Super span={:?}>", span),
+            NodeExpr::SuperCall(_, args, span) => {
+                write!(f, "<SuperCall")?;
+                for (idx, arg) in args.iter().enumerate() {
+                    write!(f, " arg_{}={:?}", idx, arg)?;
+                }
+                write!(f, " span={:?}>", span)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Neg,
+    BitNot,
+    Not,
+}
+
+impl Debug for UnaryOp {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            UnaryOp::Neg => write!(f, "-"),
+            UnaryOp::BitNot => write!(f, "~"),
+            UnaryOp::Not => write!(f, "!"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Mul,
+    Div,
+    Mod,
+    Add,
+    Sub,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl Debug for BinaryOp {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            BinaryOp::Mul => write!(f, "*"),
+            BinaryOp::Div => write!(f, "/"),
+            BinaryOp::Mod => write!(f, "%"),
+            BinaryOp::Add => write!(f, "+"),
+            BinaryOp::Sub => write!(f, "-"),
+            BinaryOp::Eq => write!(f, "=="),
+            BinaryOp::Ne => write!(f, "!="),
+            BinaryOp::Lt => write!(f, "<"),
+            BinaryOp::Le => write!(f, "<="),
+            BinaryOp::Gt => write!(f, ">"),
+            BinaryOp::Ge => write!(f, ">="),
+            BinaryOp::And => write!(f, "&&"),
+            BinaryOp::Or => write!(f, "||"),
         }
     }
 }
 
 pub enum NodeLiteral {
     IntLit(i64),
+    FloatLit(f64),
+    BoolLit(bool),
+    // Characters are 8 bits.
+    CharLit(u8),
+    Str(String),
 }
 
 impl Debug for NodeLiteral {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
             NodeLiteral::IntLit(x) => write!(f, "type=int value={}", x),
+            NodeLiteral::FloatLit(x) => write!(f, "type=float value={}", x),
+            NodeLiteral::BoolLit(x) => write!(f, "type=bool value={}", x),
+            NodeLiteral::CharLit(x) => write!(f, "type=char value={:?}", *x as char),
+            NodeLiteral::Str(x) => write!(f, "type=string value={:?}", x),
         }
     }
 }
 
 pub struct NodeIdent {
     pub name: String,
+    pub span: Span,
 }
 
 impl Debug for NodeIdent {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "name={}", self.name)
+        write!(f, "name={} span={:?}", self.name, self.span)
     }
 }
 
 pub struct NodeType {
     pub meta: TypeMeta,
+    pub span: Span,
 }
 
 impl Debug for NodeType {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        write!(f, "<Type meta={:?}>", self.meta)
+        write!(f, "<Type meta={:?} span={:?}>", self.meta, self.span)
     }
 }
 
+#[derive(Clone, PartialEq)]
 pub enum TypeMeta {
     Primitive(PrimitiveType),
+    // A string is a pointer to its length (8 bytes), followed by its bytes (UTF-8).
+    String,
+    // A fixed number of elements of a type, which are stored one after the other.
+    Array(Box<TypeMeta>, usize),
+    // Any number of elements of a type. A list is a pointer to its length, its capacity and the
+    // address of its elements (8 bytes each), which are on the heap and grow as needed.
+    List(Box<TypeMeta>),
+    // An object of a class. An object is a pointer to its fields, which are on the heap, so
+    // copying an object only copies the pointer.
+    Class(String),
+    // A variant of an enumeration, which is the number of the variant. The parser cannot tell an
+    // enumeration from a class by its name, so it is a Class until the analysis resolves it.
+    Enum(String),
+    // The type of a call to a function that does not return a value. Only builtin functions can
+    // be declared like that for now.
+    Void,
+}
+
+impl TypeMeta {
+    // Floats are kept in the floating point registers, every other value in the integer ones.
+    pub fn is_float(&self) -> bool {
+        *self == TypeMeta::Primitive(PrimitiveType::Float)
+    }
+
+    // The value of an array is the address of its elements, which are copied when the array is.
+    pub fn is_array(&self) -> bool {
+        matches!(self, TypeMeta::Array(..))
+    }
+
+    // The type of the elements of an array or a list.
+    pub fn element_type(&self) -> Option<&TypeMeta> {
+        match self {
+            TypeMeta::Array(element, _) | TypeMeta::List(element) => Some(element),
+            _ => None,
+        }
+    }
 }
 
 impl Debug for TypeMeta {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
             TypeMeta::Primitive(primitive_type) => write!(f, "{:?}", primitive_type),
+            TypeMeta::String => write!(f, "string"),
+            TypeMeta::Array(element, len) => write!(f, "[{:?}; {}]", element, len),
+            TypeMeta::List(element) => write!(f, "[{:?}]", element),
+            TypeMeta::Class(name) | TypeMeta::Enum(name) => write!(f, "{}", name),
+            TypeMeta::Void => write!(f, "void"),
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
 pub enum PrimitiveType {
     Int,
+    Float,
+    Bool,
+    Char,
 }
 
 impl Debug for PrimitiveType {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
             PrimitiveType::Int => write!(f, "int"),
+            PrimitiveType::Float => write!(f, "float"),
+            PrimitiveType::Bool => write!(f, "bool"),
+            PrimitiveType::Char => write!(f, "char"),
         }
     }
 }
@@ -1,140 +1,883 @@
 use std::iter::Peekable;
 use std::slice::Iter;
 
+use super::super::diagnostic::Diagnostic;
 use super::super::lexer::tokens::Token;
 use super::super::lexer::tokens::TokenType;
 use super::ast::*;
 
-pub fn parse_prog(tokens: Vec<Token>) -> NodeProg {
+type ParseResult<T> = Result<T, Diagnostic>;
+
+pub fn parse_prog(tokens: Vec<Token>) -> Result<NodeProg, Vec<Diagnostic>> {
+    let (prog, diagnostics) = parse_prog_partial(&tokens);
+    if diagnostics.is_empty() {
+        Ok(prog)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+// Parses as much of the program as possible. Every syntax error is reported, and the parser
+// recovers from it by skipping the broken statement, member or function (panic-mode recovery). The
+// returned program contains everything that could be parsed, which is useful for tools that have
+// to work with programs that are being edited.
+pub fn parse_prog_partial(tokens: &[Token]) -> (NodeProg, Vec<Diagnostic>) {
     let mut token_iter = tokens.iter().peekable();
+    let mut classes: Vec<NodeClass> = Vec::new();
+    let mut enums: Vec<NodeEnum> = Vec::new();
     let mut functions: Vec<NodeFunc> = Vec::new();
-    while let Some(token) = token_iter.peek() {
-        if token.token_type == TokenType::EOF {
-            break;
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    while peek_token(&mut token_iter).token_type != TokenType::EOF {
+        let remaining = token_iter.len();
+        let result = match peek_token(&mut token_iter).token_type {
+            TokenType::Class => {
+                parse_class(&mut token_iter, &mut diagnostics).map(|class| classes.push(class))
+            }
+            TokenType::Enum => parse_enum(&mut token_iter).map(|node_enum| enums.push(node_enum)),
+            _ => parse_func(&mut token_iter, &mut diagnostics).map(|func| functions.push(func)),
+        };
+        match result {
+            Ok(()) => {}
+            Err(diagnostic) => {
+                diagnostics.push(diagnostic);
+                // Make sure we move forward, even if the function was broken from its first token.
+                if token_iter.len() == remaining {
+                    token_iter.next();
+                }
+                sync_to_func_start(&mut token_iter);
+            }
+        }
+    }
+    let eof = tokens.last().expect("Error, no EOF token found");
+    let prog = NodeProg {
+        classes,
+        enums,
+        functions,
+        span: Span::new(0, eof.span.end),
+    };
+    (prog, diagnostics)
+}
+
+// Skips tokens until the start of the next function header (an identifier followed by `(` outside
+// of any block), class or enum, or the end of the file.
+fn sync_to_func_start(token_iter: &mut Peekable<Iter<Token>>) {
+    let mut depth = 0;
+    loop {
+        match peek_token(token_iter).token_type {
+            TokenType::EOF => return,
+            TokenType::Class | TokenType::Enum if depth == 0 => return,
+            TokenType::Id if depth == 0 => {
+                let mut lookahead = token_iter.clone();
+                lookahead.next();
+                if peek_token(&mut lookahead).token_type == TokenType::LP {
+                    return;
+                }
+            }
+            TokenType::LB => depth += 1,
+            TokenType::RB if depth > 0 => depth -= 1,
+            _ => {}
+        }
+        token_iter.next();
+    }
+}
+
+// Skips tokens until the end of the broken statement. That is either right after the next `;`,
+// right after a block the statement opened (like the body of an `if` with a broken condition), or
+// right before the `}` that closes the block the statement is in.
+fn sync_to_stmt_end(token_iter: &mut Peekable<Iter<Token>>) {
+    let mut depth = 0;
+    loop {
+        match peek_token(token_iter).token_type {
+            TokenType::EOF => return,
+            TokenType::Semi if depth == 0 => {
+                token_iter.next();
+                return;
+            }
+            TokenType::RB if depth == 0 => return,
+            TokenType::RB if depth == 1 => {
+                token_iter.next();
+                return;
+            }
+            TokenType::RB => depth -= 1,
+            TokenType::LB => depth += 1,
+            _ => {}
+        }
+        token_iter.next();
+    }
+}
+
+// Skips tokens until the start of the next member of a class (its visibility), or right before the
+// `}` that closes the class.
+fn sync_to_member_start(token_iter: &mut Peekable<Iter<Token>>) {
+    let mut depth = 0;
+    loop {
+        match peek_token(token_iter).token_type {
+            TokenType::EOF => return,
+            TokenType::Pub | TokenType::Prv if depth == 0 => return,
+            TokenType::RB if depth == 0 => return,
+            TokenType::RB => depth -= 1,
+            TokenType::LB => depth += 1,
+            _ => {}
+        }
+        token_iter.next();
+    }
+}
+
+fn parse_class(
+    token_iter: &mut Peekable<Iter<Token>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> ParseResult<NodeClass> {
+    let start = parse_symbol(token_iter, TokenType::Class)?;
+    let ident = parse_ident(token_iter)?;
+    let base = match peek_token(token_iter).token_type {
+        TokenType::Colon => {
+            token_iter.next();
+            Some(parse_ident(token_iter)?)
+        }
+        _ => None,
+    };
+    parse_symbol(token_iter, TokenType::LB)?;
+    let mut fields: Vec<NodeField> = Vec::new();
+    let mut constructors: Vec<NodeMethod> = Vec::new();
+    let mut methods: Vec<NodeMethod> = Vec::new();
+    while !matches!(
+        peek_token(token_iter).token_type,
+        TokenType::RB | TokenType::EOF
+    ) {
+        let remaining = token_iter.len();
+        let result = parse_member(token_iter, diagnostics).map(|member| match member {
+            Member::Field(field) => fields.push(field),
+            Member::Method(method) if method.func.ident.name == ident.name => {
+                constructors.push(method)
+            }
+            Member::Method(method) => methods.push(method),
+        });
+        if let Err(diagnostic) = result {
+            diagnostics.push(diagnostic);
+            if token_iter.len() == remaining {
+                token_iter.next();
+            }
+            sync_to_member_start(token_iter);
+        }
+    }
+    let end = parse_symbol(token_iter, TokenType::RB)?;
+    Ok(NodeClass {
+        ident,
+        base,
+        fields,
+        constructors,
+        methods,
+        span: start.to(end),
+    })
+}
+
+// Parses an enum, like `enum Color { RED, GREEN, BLUE }`.
+fn parse_enum(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeEnum> {
+    let start = parse_symbol(token_iter, TokenType::Enum)?;
+    let ident = parse_ident(token_iter)?;
+    parse_symbol(token_iter, TokenType::LB)?;
+    let mut variants: Vec<NodeIdent> = Vec::new();
+    if peek_token(token_iter).token_type != TokenType::RB {
+        variants.push(parse_ident(token_iter)?);
+        while peek_token(token_iter).token_type == TokenType::Comma {
+            token_iter.next();
+            variants.push(parse_ident(token_iter)?);
+        }
+    }
+    let end = parse_symbol(token_iter, TokenType::RB)?;
+    Ok(NodeEnum {
+        ident,
+        variants,
+        span: start.to(end),
+    })
+}
+
+enum Member {
+    Field(NodeField),
+    Method(NodeMethod),
+}
+
+// Parses a field (`pub x: int;`) or a method (`pub get(): int = { ... }`) of a class. Every member
+// starts with its visibility. Constructors are the methods with the name of the class.
+fn parse_member(
+    token_iter: &mut Peekable<Iter<Token>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> ParseResult<Member> {
+    let token = peek_token(token_iter);
+    let visibility = match token.token_type {
+        TokenType::Pub => Visibility::Public,
+        TokenType::Prv => Visibility::Private,
+        _ => {
+            return Err(
+                unexpected_token("the visibility of a member".to_string(), token)
+                    .with_note("every member of a class starts with `pub` or `prv`".to_string()),
+            )
         }
-        functions.push(parse_func(&mut token_iter));
+    };
+    token_iter.next();
+    let mut lookahead = token_iter.clone();
+    lookahead.next();
+    if peek_token(&mut lookahead).token_type != TokenType::LP {
+        let ident = parse_ident(token_iter)?;
+        parse_symbol(token_iter, TokenType::Colon)?;
+        let f_type = parse_type(token_iter)?;
+        let end = parse_symbol(token_iter, TokenType::Semi)?;
+        return Ok(Member::Field(NodeField {
+            visibility,
+            ident,
+            f_type,
+            span: token.span.to(end),
+        }));
     }
-    NodeProg { functions }
+    let func = parse_func(token_iter, diagnostics)?;
+    Ok(Member::Method(NodeMethod {
+        visibility,
+        span: token.span.to(func.span),
+        func,
+    }))
 }
 
-fn parse_func(token_iter: &mut Peekable<Iter<Token>>) -> NodeFunc {
-    let ident = parse_ident(token_iter);
-    parse_symbol(token_iter, TokenType::LP);
-    parse_symbol(token_iter, TokenType::RP);
-    parse_symbol(token_iter, TokenType::Colon);
-    let r_type = parse_type(token_iter);
-    parse_symbol(token_iter, TokenType::Assign);
-    let block = parse_block(token_iter);
-    NodeFunc {
+fn parse_func(
+    token_iter: &mut Peekable<Iter<Token>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> ParseResult<NodeFunc> {
+    let ident = parse_ident(token_iter)?;
+    parse_symbol(token_iter, TokenType::LP)?;
+    let params = parse_comma_separated(token_iter, parse_param)?;
+    parse_symbol(token_iter, TokenType::RP)?;
+    parse_symbol(token_iter, TokenType::Colon)?;
+    let r_type = parse_type(token_iter)?;
+    parse_symbol(token_iter, TokenType::Assign)?;
+    let block = parse_block(token_iter, diagnostics, false)?;
+    Ok(NodeFunc {
+        span: ident.span.to(block.span),
         ident,
+        params,
         r_type,
         block,
+    })
+}
+
+fn parse_param(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeParam> {
+    let ident = parse_ident(token_iter)?;
+    parse_symbol(token_iter, TokenType::Colon)?;
+    let p_type = parse_type(token_iter)?;
+    Ok(NodeParam {
+        span: ident.span.to(p_type.span),
+        ident,
+        p_type,
+    })
+}
+
+// Parses a (possibly empty) comma separated list of items that ends right before a `)`.
+fn parse_comma_separated<T>(
+    token_iter: &mut Peekable<Iter<Token>>,
+    parse_item: fn(&mut Peekable<Iter<Token>>) -> ParseResult<T>,
+) -> ParseResult<Vec<T>> {
+    let mut items: Vec<T> = Vec::new();
+    if peek_token(token_iter).token_type == TokenType::RP {
+        return Ok(items);
     }
+    items.push(parse_item(token_iter)?);
+    while peek_token(token_iter).token_type == TokenType::Comma {
+        token_iter.next();
+        items.push(parse_item(token_iter)?);
+    }
+    Ok(items)
+}
+
+// The token stream always ends with an EOF token, and tokens are only consumed once they are known
+// to be what the parser expects, so we never try to read past the end of the stream.
+fn peek_token<'a>(token_iter: &mut Peekable<Iter<'a, Token>>) -> &'a Token {
+    token_iter.peek().expect("Error, read past the EOF token")
+}
+
+fn unexpected_token(expected: String, token: &Token) -> Diagnostic {
+    Diagnostic::error(
+        format!("Expected {}, got {:?} instead.", expected, token.token_type),
+        token.span,
+    )
 }
 
-fn parse_ident(token_iter: &mut Peekable<Iter<Token>>) -> NodeIdent {
-    let token = token_iter.next().unwrap();
+fn parse_ident(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeIdent> {
+    let token = peek_token(token_iter);
     if token.token_type != TokenType::Id {
-        panic!(
-            "Expected an identifier, got {:?} instead.",
-            token.token_type
-        );
+        return Err(unexpected_token("an identifier".to_string(), token));
     }
-    NodeIdent {
+    token_iter.next();
+    Ok(NodeIdent {
         name: token.value.clone(),
-    }
+        span: token.span,
+    })
 }
 
-fn parse_symbol(token_iter: &mut Peekable<Iter<Token>>, symbol: TokenType) {
-    let token = token_iter.next().unwrap();
+fn parse_symbol(token_iter: &mut Peekable<Iter<Token>>, symbol: TokenType) -> ParseResult<Span> {
+    let token = peek_token(token_iter);
     if token.token_type != symbol {
-        panic!(
-            "Expected the symbol {:?}, got {:?} instead.",
-            symbol, token.token_type
-        );
+        let diagnostic = unexpected_token(format!("the symbol {:?}", symbol), token);
+        return Err(match symbol {
+            TokenType::Semi => {
+                diagnostic.with_note("every statement must end with a semicolon".to_string())
+            }
+            _ => diagnostic,
+        });
     }
+    token_iter.next();
+    Ok(token.span)
 }
 
-fn parse_type(token_iter: &mut Peekable<Iter<Token>>) -> NodeType {
-    let token = token_iter.next().unwrap();
+fn parse_type(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeType> {
+    let token = peek_token(token_iter);
     let meta = match token.token_type {
         TokenType::Int => TypeMeta::Primitive(PrimitiveType::Int),
-        _ => panic!("Expected a known type, got {:?} instead.", token.token_type),
+        TokenType::Float => TypeMeta::Primitive(PrimitiveType::Float),
+        TokenType::Bool => TypeMeta::Primitive(PrimitiveType::Bool),
+        TokenType::Char => TypeMeta::Primitive(PrimitiveType::Char),
+        TokenType::String => TypeMeta::String,
+        TokenType::LSB => return parse_array_type(token_iter),
+        TokenType::Id => TypeMeta::Class(token.value.clone()),
+        _ => return Err(unexpected_token("a known type".to_string(), token)),
     };
-    NodeType { meta }
+    token_iter.next();
+    Ok(NodeType {
+        meta,
+        span: token.span,
+    })
+}
+
+// Parses an array type, like `[int; 5]`, or a list type, like `[int]`.
+fn parse_array_type(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeType> {
+    let start = parse_symbol(token_iter, TokenType::LSB)?;
+    let element = parse_type(token_iter)?;
+    if peek_token(token_iter).token_type == TokenType::RSB {
+        let end = parse_symbol(token_iter, TokenType::RSB)?;
+        return Ok(NodeType {
+            meta: TypeMeta::List(Box::new(element.meta)),
+            span: start.to(end),
+        });
+    }
+    let len = parse_array_len(token_iter)?;
+    let end = parse_symbol(token_iter, TokenType::RSB)?;
+    Ok(NodeType {
+        meta: TypeMeta::Array(Box::new(element.meta), len),
+        span: start.to(end),
+    })
+}
+
+// Parses the `; 5` that gives the length of an array, in an array type or an array literal. The
+// length has to be known when the program is compiled, so it can only be an integer literal.
+fn parse_array_len(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<usize> {
+    // This is not the end of a statement, so parse_symbol would add the wrong note.
+    let token = peek_token(token_iter);
+    if token.token_type != TokenType::Semi {
+        return Err(unexpected_token("the symbol Semi".to_string(), token));
+    }
+    token_iter.next();
+    let token = peek_token(token_iter);
+    if token.token_type != TokenType::IntLit {
+        return Err(unexpected_token(
+            "the length of the array".to_string(),
+            token,
+        ));
+    }
+    token_iter.next();
+    match token.value.parse() {
+        Ok(0) => Err(Diagnostic::error(
+            "Arrays must have at least one element.".to_string(),
+            token.span,
+        )),
+        Ok(len) => Ok(len),
+        Err(_) => Err(Diagnostic::error(
+            format!("Array length {} is too large.", token.value),
+            token.span,
+        )),
+    }
 }
 
-fn parse_block(token_iter: &mut Peekable<Iter<Token>>) -> NodeBlock {
+// in_loop is whether the block is (directly or not) inside the body of a loop, which is where
+// `break` and `continue` are allowed.
+fn parse_block(
+    token_iter: &mut Peekable<Iter<Token>>,
+    diagnostics: &mut Vec<Diagnostic>,
+    in_loop: bool,
+) -> ParseResult<NodeBlock> {
     let mut stmts: Vec<NodeStmt> = Vec::new();
-    parse_symbol(token_iter, TokenType::LB);
-    while let Some(token) = token_iter.peek() {
-        if token.token_type == TokenType::RB {
-            break;
+    let start = parse_symbol(token_iter, TokenType::LB)?;
+    while !matches!(
+        peek_token(token_iter).token_type,
+        TokenType::RB | TokenType::EOF
+    ) {
+        match parse_stmt(token_iter, diagnostics, in_loop) {
+            Ok(stmt) => stmts.push(stmt),
+            Err(diagnostic) => {
+                diagnostics.push(diagnostic);
+                sync_to_stmt_end(token_iter);
+            }
         }
-        stmts.push(parse_stmt(token_iter));
     }
-    parse_symbol(token_iter, TokenType::RB);
-    NodeBlock { stmts }
+    // A block that is never closed still keeps the statements that were parsed in it.
+    let end = match parse_symbol(token_iter, TokenType::RB) {
+        Ok(end) => end,
+        Err(diagnostic) => {
+            let end = diagnostic.span;
+            diagnostics.push(diagnostic);
+            end
+        }
+    };
+    Ok(NodeBlock {
+        stmts,
+        span: start.to(end),
+    })
 }
 
-fn parse_stmt(token_iter: &mut Peekable<Iter<Token>>) -> NodeStmt {
-    let stmt: NodeStmt;
-    match token_iter.peek() {
-        Some(token) => match token.token_type {
-            TokenType::Ret => stmt = parse_return_stmt(token_iter),
-            TokenType::Id => stmt = parse_assign_stmt(token_iter),
-            _ => panic!(
-                "Expected the start of a statement, got {:?} instead.",
-                token.token_type
+fn parse_stmt(
+    token_iter: &mut Peekable<Iter<Token>>,
+    diagnostics: &mut Vec<Diagnostic>,
+    in_loop: bool,
+) -> ParseResult<NodeStmt> {
+    let token = peek_token(token_iter);
+    let stmt = match token.token_type {
+        // Control flow statements end with a block instead of a semicolon
+        TokenType::If => return parse_if_stmt(token_iter, diagnostics, in_loop),
+        TokenType::While => return parse_while_stmt(token_iter, diagnostics),
+        TokenType::Ret => parse_return_stmt(token_iter)?,
+        TokenType::Break | TokenType::Continue => parse_loop_jump_stmt(token_iter, in_loop)?,
+        TokenType::Const => parse_assign_stmt(token_iter)?,
+        TokenType::This | TokenType::Super => parse_postfix_stmt(token_iter)?,
+        TokenType::Id => {
+            let mut lookahead = token_iter.clone();
+            lookahead.next();
+            match peek_token(&mut lookahead).token_type {
+                TokenType::Assign => parse_reassign_stmt(token_iter)?,
+                TokenType::LSB | TokenType::Dot | TokenType::LP => parse_postfix_stmt(token_iter)?,
+                _ => parse_assign_stmt(token_iter)?,
+            }
+        }
+        _ => {
+            return Err(unexpected_token(
+                "the start of a statement".to_string(),
+                token,
+            ))
+        }
+    };
+    parse_symbol(token_iter, TokenType::Semi)?;
+    Ok(stmt)
+}
+
+fn parse_if_stmt(
+    token_iter: &mut Peekable<Iter<Token>>,
+    diagnostics: &mut Vec<Diagnostic>,
+    in_loop: bool,
+) -> ParseResult<NodeStmt> {
+    let start = parse_symbol(token_iter, TokenType::If)?;
+    parse_symbol(token_iter, TokenType::LP)?;
+    let cond = parse_expr(token_iter)?;
+    parse_symbol(token_iter, TokenType::RP)?;
+    let then_block = parse_block(token_iter, diagnostics, in_loop)?;
+    let else_block = match peek_token(token_iter).token_type {
+        TokenType::Else => {
+            token_iter.next();
+            match peek_token(token_iter).token_type {
+                // An `else if` is an else block that only contains another if statement
+                TokenType::If => {
+                    let stmt = parse_if_stmt(token_iter, diagnostics, in_loop)?;
+                    let span = stmt.span();
+                    Some(NodeBlock {
+                        stmts: vec![stmt],
+                        span,
+                    })
+                }
+                _ => Some(parse_block(token_iter, diagnostics, in_loop)?),
+            }
+        }
+        _ => None,
+    };
+    let end = else_block.as_ref().unwrap_or(&then_block).span;
+    Ok(NodeStmt::If(cond, then_block, else_block, start.to(end)))
+}
+
+fn parse_while_stmt(
+    token_iter: &mut Peekable<Iter<Token>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> ParseResult<NodeStmt> {
+    let start = parse_symbol(token_iter, TokenType::While)?;
+    parse_symbol(token_iter, TokenType::LP)?;
+    let cond = parse_expr(token_iter)?;
+    parse_symbol(token_iter, TokenType::RP)?;
+    let body = parse_block(token_iter, diagnostics, true)?;
+    let span = start.to(body.span);
+    Ok(NodeStmt::While(cond, body, span))
+}
+
+// Parses a `break` or a `continue`, which can only be used inside the body of a loop.
+fn parse_loop_jump_stmt(
+    token_iter: &mut Peekable<Iter<Token>>,
+    in_loop: bool,
+) -> ParseResult<NodeStmt> {
+    let token = peek_token(token_iter);
+    let keyword = match token.token_type {
+        TokenType::Break => "break",
+        _ => "continue",
+    };
+    if !in_loop {
+        return Err(
+            Diagnostic::error(format!("`{}` outside of a loop.", keyword), token.span).with_note(
+                format!("`{}` can only be used inside a while loop", keyword),
             ),
-        },
-        None => panic!("Unexpected end of file."),
+        );
     }
-    parse_symbol(token_iter, TokenType::Semi);
-    stmt
+    token_iter.next();
+    Ok(match token.token_type {
+        TokenType::Break => NodeStmt::Break(token.span),
+        _ => NodeStmt::Continue(token.span),
+    })
 }
 
-fn parse_return_stmt(token_iter: &mut Peekable<Iter<Token>>) -> NodeStmt {
-    parse_symbol(token_iter, TokenType::Ret);
-    let expr = parse_expr(token_iter);
-    NodeStmt::Return(expr)
+fn parse_return_stmt(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeStmt> {
+    let start = parse_symbol(token_iter, TokenType::Ret)?;
+    let expr = parse_expr(token_iter)?;
+    let span = start.to(expr.span());
+    Ok(NodeStmt::Return(expr, span))
 }
 
-fn parse_assign_stmt(token_iter: &mut Peekable<Iter<Token>>) -> NodeStmt {
-    let ident = parse_ident(token_iter);
-    parse_symbol(token_iter, TokenType::Colon);
-    let a_type = parse_type(token_iter);
-    parse_symbol(token_iter, TokenType::Assign);
-    let expr = parse_expr(token_iter);
-    NodeStmt::Assign(ident, a_type, expr)
+fn parse_assign_stmt(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeStmt> {
+    let const_span = match peek_token(token_iter).token_type {
+        TokenType::Const => Some(parse_symbol(token_iter, TokenType::Const)?),
+        _ => None,
+    };
+    let ident = parse_ident(token_iter)?;
+    parse_symbol(token_iter, TokenType::Colon)?;
+    let a_type = parse_type(token_iter)?;
+    parse_symbol(token_iter, TokenType::Assign)?;
+    let expr = parse_expr(token_iter)?;
+    let span = const_span.unwrap_or(ident.span).to(expr.span());
+    Ok(NodeStmt::Assign(
+        ident,
+        a_type,
+        expr,
+        const_span.is_none(),
+        span,
+    ))
 }
 
-fn parse_expr(token_iter: &mut Peekable<Iter<Token>>) -> NodeExpr {
-    match token_iter.peek() {
-        Some(token) => match token.token_type {
-            TokenType::IntLit => parse_literal_expression(token_iter),
-            TokenType::Id => parse_ident_expression(token_iter),
-            _ => panic!(
-                "Expected the start of an expression, got {:?} instead.",
-                token.token_type
-            ),
-        },
-        None => panic!("Unexpected end of file."),
+fn parse_reassign_stmt(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeStmt> {
+    let ident = parse_ident(token_iter)?;
+    parse_symbol(token_iter, TokenType::Assign)?;
+    let expr = parse_expr(token_iter)?;
+    let span = ident.span.to(expr.span());
+    Ok(NodeStmt::Reassign(ident, expr, span))
+}
+
+// Parses an assignment to an element of an array (`a[0] = 1`) or a field of an object
+// (`this.x = 1`), or a call to a function or a method (`reset()` or `make().reset()`).
+fn parse_postfix_stmt(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeStmt> {
+    let target = parse_postfix_expression(token_iter)?;
+    if let NodeExpr::Call(..) | NodeExpr::MethodCall(..) | NodeExpr::SuperCall(..) = target {
+        if peek_token(token_iter).token_type != TokenType::Assign {
+            let span = target.span();
+            return Ok(NodeStmt::Expr(target, span));
+        }
+    }
+    parse_symbol(token_iter, TokenType::Assign)?;
+    let expr = parse_expr(token_iter)?;
+    let span = target.span().to(expr.span());
+    match target {
+        NodeExpr::Index(array, index, _) => Ok(NodeStmt::IndexAssign(*array, *index, expr, span)),
+        NodeExpr::Member(object, field, _) => {
+            Ok(NodeStmt::MemberAssign(*object, field, expr, span))
+        }
+        _ => Err(Diagnostic::error(
+            "Only elements and fields can be assigned to.".to_string(),
+            target.span(),
+        )),
+    }
+}
+
+// Binary operators and their precedence (higher binds tighter). All of them are left associative.
+fn binary_op(token_type: TokenType) -> Option<(BinaryOp, u8)> {
+    match token_type {
+        TokenType::Star => Some((BinaryOp::Mul, 6)),
+        TokenType::Slash => Some((BinaryOp::Div, 6)),
+        TokenType::Percent => Some((BinaryOp::Mod, 6)),
+        TokenType::Plus => Some((BinaryOp::Add, 5)),
+        TokenType::Minus => Some((BinaryOp::Sub, 5)),
+        TokenType::Lt => Some((BinaryOp::Lt, 4)),
+        TokenType::Le => Some((BinaryOp::Le, 4)),
+        TokenType::Gt => Some((BinaryOp::Gt, 4)),
+        TokenType::Ge => Some((BinaryOp::Ge, 4)),
+        TokenType::Eq => Some((BinaryOp::Eq, 3)),
+        TokenType::Ne => Some((BinaryOp::Ne, 3)),
+        TokenType::And => Some((BinaryOp::And, 2)),
+        TokenType::Or => Some((BinaryOp::Or, 1)),
+        _ => None,
+    }
+}
+
+fn parse_expr(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    parse_binary_expression(token_iter, 0)
+}
+
+// Precedence climbing: parses a chain of binary operations whose operators all have a precedence
+// of at least min_precedence. The right hand side of an operator only takes operators that bind
+// tighter than it, which makes operators of the same precedence group to the left.
+fn parse_binary_expression(
+    token_iter: &mut Peekable<Iter<Token>>,
+    min_precedence: u8,
+) -> ParseResult<NodeExpr> {
+    let mut lhs = parse_unary_expression(token_iter)?;
+    while let Some((op, precedence)) = binary_op(peek_token(token_iter).token_type) {
+        if precedence < min_precedence {
+            break;
+        }
+        token_iter.next();
+        let rhs = parse_binary_expression(token_iter, precedence + 1)?;
+        let span = lhs.span().to(rhs.span());
+        lhs = NodeExpr::Binary(op, Box::new(lhs), Box::new(rhs), span);
     }
+    Ok(lhs)
 }
 
-fn parse_literal_expression(token_iter: &mut Peekable<Iter<Token>>) -> NodeExpr {
-    let token = token_iter.next().unwrap();
+fn parse_unary_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let token = peek_token(token_iter);
+    let op = match token.token_type {
+        TokenType::Minus => UnaryOp::Neg,
+        TokenType::Tilde => UnaryOp::BitNot,
+        TokenType::Not => UnaryOp::Not,
+        _ => return parse_postfix_expression(token_iter),
+    };
+    token_iter.next();
+    // The digits of a negated integer literal are parsed together with the minus, since the
+    // smallest int has no positive counterpart to negate. A literal that is indexed or has a
+    // method called on it is left to the postfix expression (and the type checker).
+    let literal = peek_token(token_iter);
+    if matches!(op, UnaryOp::Neg) && literal.token_type == TokenType::IntLit {
+        let mut lookahead = token_iter.clone();
+        lookahead.next();
+        if !matches!(
+            peek_token(&mut lookahead).token_type,
+            TokenType::LSB | TokenType::Dot
+        ) {
+            token_iter.next();
+            let span = token.span.to(literal.span);
+            return match format!("-{}", literal.value).parse() {
+                Ok(value) => Ok(NodeExpr::Literal(NodeLiteral::IntLit(value), span)),
+                Err(_) => Err(Diagnostic::error(
+                    format!("Integer literal -{} is too small.", literal.value),
+                    span,
+                )),
+            };
+        }
+    }
+    let expr = parse_unary_expression(token_iter)?;
+    let span = token.span.to(expr.span());
+    Ok(NodeExpr::Unary(op, Box::new(expr), span))
+}
+
+// Indexing, accessing fields and calling methods bind tighter than any operator, so `-a[0]` is
+// `-(a[0])`.
+fn parse_postfix_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let mut expr = parse_primary_expression(token_iter)?;
+    loop {
+        match peek_token(token_iter).token_type {
+            TokenType::LSB => {
+                token_iter.next();
+                let index = parse_expr(token_iter)?;
+                let end = parse_symbol(token_iter, TokenType::RSB)?;
+                let span = expr.span().to(end);
+                expr = NodeExpr::Index(Box::new(expr), Box::new(index), span);
+            }
+            TokenType::Dot => {
+                token_iter.next();
+                let field = parse_ident(token_iter)?;
+                if peek_token(token_iter).token_type == TokenType::LP {
+                    token_iter.next();
+                    let args = parse_comma_separated(token_iter, parse_expr)?;
+                    let end = parse_symbol(token_iter, TokenType::RP)?;
+                    let span = expr.span().to(end);
+                    expr = NodeExpr::MethodCall(Box::new(expr), field, args, span);
+                } else {
+                    let span = expr.span().to(field.span);
+                    expr = NodeExpr::Member(Box::new(expr), field, span);
+                }
+            }
+            _ => return Ok(expr),
+        }
+    }
+}
+
+fn parse_primary_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let token = peek_token(token_iter);
     match token.token_type {
-        TokenType::IntLit => NodeExpr::Literal(NodeLiteral::IntLit(token.value.parse().unwrap())),
-        _ => panic!(
-            "Expected an integer literal, got {:?} instead.",
-            token.token_type
-        ),
+        TokenType::IntLit
+        | TokenType::FloatLit
+        | TokenType::BoolLit
+        | TokenType::CharLit
+        | TokenType::StrLit => parse_literal_expression(token_iter),
+        TokenType::Id => {
+            let mut lookahead = token_iter.clone();
+            lookahead.next();
+            if peek_token(&mut lookahead).token_type == TokenType::LP {
+                parse_call_expression(token_iter)
+            } else {
+                parse_ident_expression(token_iter)
+            }
+        }
+        TokenType::LP => parse_paren_expression(token_iter),
+        TokenType::This => {
+            token_iter.next();
+            Ok(NodeExpr::This(token.span))
+        }
+        // `super` is either followed by the arguments of a constructor of the base class, or by a
+        // method of it (which the postfix expression parses).
+        TokenType::Super => {
+            token_iter.next();
+            if peek_token(token_iter).token_type != TokenType::LP {
+                return Ok(NodeExpr::Super(token.span));
+            }
+            token_iter.next();
+            let args = parse_comma_separated(token_iter, parse_expr)?;
+            let end = parse_symbol(token_iter, TokenType::RP)?;
+            let ident = NodeIdent {
+                name: "super".to_string(),
+                span: token.span,
+            };
+            Ok(NodeExpr::SuperCall(ident, args, token.span.to(end)))
+        }
+        TokenType::LSB => parse_array_expression(token_iter),
+        _ => Err(unexpected_token(
+            "the start of an expression".to_string(),
+            token,
+        )),
     }
 }
 
-fn parse_ident_expression(token_iter: &mut Peekable<Iter<Token>>) -> NodeExpr {
-    let ident = parse_ident(token_iter);
-    NodeExpr::Ident(ident)
+fn parse_literal_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let token = peek_token(token_iter);
+    let literal = match token.token_type {
+        TokenType::IntLit => match token.value.parse() {
+            Ok(value) => NodeLiteral::IntLit(value),
+            Err(_) => {
+                return Err(Diagnostic::error(
+                    format!("Integer literal {} is too large.", token.value),
+                    token.span,
+                ))
+            }
+        },
+        TokenType::FloatLit => match token.value.parse::<f64>() {
+            Ok(value) if value.is_finite() => NodeLiteral::FloatLit(value),
+            _ => {
+                return Err(Diagnostic::error(
+                    format!("Float literal {} is too large.", token.value),
+                    token.span,
+                ))
+            }
+        },
+        TokenType::BoolLit => NodeLiteral::BoolLit(token.value == "true"),
+        // The lexer already turned the literal (which might be an escape) into its character,
+        // and made sure it fits in 8 bits.
+        TokenType::CharLit => {
+            NodeLiteral::CharLit(token.value.chars().next().expect("Error, empty char") as u8)
+        }
+        TokenType::StrLit => NodeLiteral::Str(token.value.clone()),
+        _ => return Err(unexpected_token("a literal".to_string(), token)),
+    };
+    token_iter.next();
+    Ok(NodeExpr::Literal(literal, token.span))
+}
+
+fn parse_ident_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let ident = parse_ident(token_iter)?;
+    Ok(NodeExpr::Ident(ident))
+}
+
+fn parse_paren_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let start = parse_symbol(token_iter, TokenType::LP)?;
+    let expr = parse_expr(token_iter)?;
+    let end = parse_symbol(token_iter, TokenType::RP)?;
+    Ok(NodeExpr::Paren(Box::new(expr), start.to(end)))
+}
+
+// Parses an array literal, which either lists every element (`[1, 2, 3]`), or repeats the same
+// value a number of times (`[0; 5]`). Without any elements (`[]`), it is a new empty list.
+fn parse_array_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let start = parse_symbol(token_iter, TokenType::LSB)?;
+    if peek_token(token_iter).token_type == TokenType::RSB {
+        let end = parse_symbol(token_iter, TokenType::RSB)?;
+        return Ok(NodeExpr::EmptyList(start.to(end)));
+    }
+    let first = parse_expr(token_iter)?;
+    if peek_token(token_iter).token_type == TokenType::Semi {
+        let count = parse_array_len(token_iter)?;
+        let end = parse_symbol(token_iter, TokenType::RSB)?;
+        return Ok(NodeExpr::ArrayRepeat(Box::new(first), count, start.to(end)));
+    }
+    let mut elements = vec![first];
+    while peek_token(token_iter).token_type == TokenType::Comma {
+        token_iter.next();
+        elements.push(parse_expr(token_iter)?);
+    }
+    let end = parse_symbol(token_iter, TokenType::RSB)?;
+    Ok(NodeExpr::Array(elements, start.to(end)))
+}
+
+fn parse_call_expression(token_iter: &mut Peekable<Iter<Token>>) -> ParseResult<NodeExpr> {
+    let ident = parse_ident(token_iter)?;
+    parse_symbol(token_iter, TokenType::LP)?;
+    let args = parse_comma_separated(token_iter, parse_expr)?;
+    let end = parse_symbol(token_iter, TokenType::RP)?;
+    let span = ident.span.to(end);
+    Ok(NodeExpr::Call(ident, args, span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::lexer::lexer::get_tokens;
+    use super::*;
+
+    #[test]
+    fn parse_prog_partial_keeps_what_parses() {
+        let src = "
+            class Point {
+                pub x: int;
+                pub Point(x: int): Point = {
+                    this.x = x;
+                }
+            }
+            broken(x: ): int = {
+                return x;
+            }
+            main(): int = {
+                return 0;
+            }
+        ";
+        let tokens = get_tokens(src.to_string()).expect("Error, the program should lex");
+        let (prog, diagnostics) = parse_prog_partial(&tokens);
+        assert_eq!(diagnostics.len(), 1);
+        let classes: Vec<&str> = prog.classes.iter().map(|c| c.ident.name.as_str()).collect();
+        assert_eq!(classes, ["Point"]);
+        let functions: Vec<&str> = prog
+            .functions
+            .iter()
+            .map(|f| f.ident.name.as_str())
+            .collect();
+        assert_eq!(functions, ["main"]);
+    }
+
+    #[test]
+    fn parse_prog_partial_reports_the_stmt_after_a_broken_block() {
+        let src = "main(): int = { if (1 < ) { return 1; } x: int = ; y: int = 2 +; return 0; }";
+        let tokens = get_tokens(src.to_string()).expect("Error, the program should lex");
+        let (_, diagnostics) = parse_prog_partial(&tokens);
+        let starts: Vec<usize> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.span.start)
+            .collect();
+        // The broken condition, then the missing value of x and the missing operand in y.
+        let expected = [
+            src.find(") {").unwrap(),
+            src.find("; y").unwrap(),
+            src.find("; return").unwrap(),
+        ];
+        assert_eq!(starts, expected);
+    }
 }
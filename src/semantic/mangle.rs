@@ -0,0 +1,160 @@
+use super::super::parser::ast::*;
+
+// Functions can be overloaded, so the symbol of a function has to tell its overloads apart. It is
+// made of the name of the function (after the name of its class, for constructors and methods) and
+// the types of its parameters, separated by dots:
+//     add(a: int, b: int): int           -> objection_add.ii
+//     Point.distance(other: Point): float -> objection_Point.distance.C5Point
+//     main(): int                         -> objection_main.v
+// Every type has a code: `i` (int), `f` (float), `b` (bool), `c` (char) and `s` (string), `A` and
+// the length before the code of the elements of an array, `L` before the code of the elements of a
+// list, and `C` (or `E`) and the length of the name before the name of a class (or an enum). A
+// function without parameters gets `v` (void), so that the symbol never ends with a dot.
+
+// The prefix of the symbols of the functions of the program. The builtin functions are part of the
+// runtime, and are prefixed with `__objection_` instead, so they can never clash.
+const PREFIX: &str = "objection_";
+
+// The symbol emitted for a function, or for a constructor or a method of a class.
+pub fn mangle(class: Option<&str>, name: &str, params: &[TypeMeta]) -> String {
+    let mut symbol = PREFIX.to_string();
+    if let Some(class) = class {
+        symbol.push_str(class);
+        symbol.push('.');
+    }
+    symbol.push_str(name);
+    symbol.push('.');
+    if params.is_empty() {
+        symbol.push('v');
+    }
+    for param in params.iter() {
+        mangle_type(param, &mut symbol);
+    }
+    symbol
+}
+
+fn mangle_type(meta_type: &TypeMeta, symbol: &mut String) {
+    match meta_type {
+        TypeMeta::Primitive(PrimitiveType::Int) => symbol.push('i'),
+        TypeMeta::Primitive(PrimitiveType::Float) => symbol.push('f'),
+        TypeMeta::Primitive(PrimitiveType::Bool) => symbol.push('b'),
+        TypeMeta::Primitive(PrimitiveType::Char) => symbol.push('c'),
+        TypeMeta::String => symbol.push('s'),
+        TypeMeta::Array(element, len) => {
+            symbol.push_str(&format!("A{}", len));
+            mangle_type(element, symbol);
+        }
+        TypeMeta::List(element) => {
+            symbol.push('L');
+            mangle_type(element, symbol);
+        }
+        TypeMeta::Class(name) => symbol.push_str(&format!("C{}{}", name.len(), name)),
+        TypeMeta::Enum(name) => symbol.push_str(&format!("E{}{}", name.len(), name)),
+        TypeMeta::Void => unreachable!("Error, a parameter cannot be void"),
+    }
+}
+
+// The symbol of the vtable of a class, which cannot be the symbol of a function since `vtable` is
+// not a list of parameters.
+pub fn vtable(class: &str) -> String {
+    format!("{}{}.vtable", PREFIX, class)
+}
+
+// Turns a symbol back into the function it was made from, e.g. `Point.distance(Point)`, or the
+// vtable of a class. It is None if the symbol was not made by mangle or vtable.
+pub fn demangle(symbol: &str) -> Option<String> {
+    let symbol = symbol.strip_prefix(PREFIX)?;
+    let (name, params) = symbol.rsplit_once('.')?;
+    if params == "vtable" {
+        return Some(format!("the vtable of {}", name));
+    }
+    let params = match params {
+        "v" => Vec::new(),
+        _ => {
+            let mut rest = params;
+            let mut types = Vec::new();
+            while !rest.is_empty() {
+                let (meta_type, after) = demangle_type(rest)?;
+                types.push(meta_type);
+                rest = after;
+            }
+            types
+        }
+    };
+    let names: Vec<String> = params
+        .iter()
+        .map(|meta_type| format!("{:?}", meta_type))
+        .collect();
+    Some(format!("{}({})", name, names.join(", ")))
+}
+
+// Reads the type at the start of a mangled list of parameters, and returns it with the rest of the
+// list.
+fn demangle_type(symbol: &str) -> Option<(TypeMeta, &str)> {
+    let mut chars = symbol.chars();
+    let code = chars.next()?;
+    let rest = chars.as_str();
+    match code {
+        'i' => Some((TypeMeta::Primitive(PrimitiveType::Int), rest)),
+        'f' => Some((TypeMeta::Primitive(PrimitiveType::Float), rest)),
+        'b' => Some((TypeMeta::Primitive(PrimitiveType::Bool), rest)),
+        'c' => Some((TypeMeta::Primitive(PrimitiveType::Char), rest)),
+        's' => Some((TypeMeta::String, rest)),
+        'A' => {
+            let (len, rest) = demangle_len(rest)?;
+            let (element, rest) = demangle_type(rest)?;
+            Some((TypeMeta::Array(Box::new(element), len), rest))
+        }
+        'L' => {
+            let (element, rest) = demangle_type(rest)?;
+            Some((TypeMeta::List(Box::new(element)), rest))
+        }
+        'C' => {
+            let (len, rest) = demangle_len(rest)?;
+            let name = rest.get(..len)?;
+            Some((TypeMeta::Class(name.to_string()), &rest[len..]))
+        }
+        'E' => {
+            let (len, rest) = demangle_len(rest)?;
+            let name = rest.get(..len)?;
+            Some((TypeMeta::Enum(name.to_string()), &rest[len..]))
+        }
+        _ => None,
+    }
+}
+
+// Reads the length at the start of a mangled type.
+fn demangle_len(symbol: &str) -> Option<(usize, &str)> {
+    let digits = symbol
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(symbol.len());
+    let len = symbol[..digits].parse().ok()?;
+    Some((len, &symbol[digits..]))
+}
+
+// Demangles every symbol in a message from the assembler or the linker, so that it names the
+// functions of the program the way they were written.
+pub fn demangle_message(message: &str) -> String {
+    let mut demangled = String::new();
+    let mut word = String::new();
+    for c in message.chars().chain(std::iter::once('\n')) {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            word.push(c);
+            continue;
+        }
+        // A symbol never ends with a dot, so a dot after it ends a sentence.
+        let symbol = word.trim_end_matches('.');
+        match demangle(symbol) {
+            Some(function) => {
+                demangled.push_str(&function);
+                demangled.push_str(&word[symbol.len()..]);
+            }
+            None => demangled.push_str(&word),
+        }
+        word.clear();
+        demangled.push(c);
+    }
+    // The newline that ended the last word is not part of the message.
+    demangled.pop();
+    demangled
+}
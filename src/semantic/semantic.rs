@@ -0,0 +1,1949 @@
+use std::collections::HashMap;
+
+use super::super::diagnostic::Diagnostic;
+use super::super::parser::ast::*;
+use super::mangle::mangle;
+
+// What the analysis knows about a function.
+#[derive(Clone)]
+pub struct FunctionSymbol {
+    pub params: Vec<TypeMeta>,
+    pub r_type: TypeMeta,
+    // The symbol that is emitted for the function, without the prefix some targets add to every
+    // symbol. Functions of the program get the symbol made by mangle (which is prefixed with
+    // `objection_`), while builtin functions are part of the runtime and are prefixed with
+    // `__objection_`, so they can never clash.
+    pub symbol: String,
+    pub kind: FunctionKind,
+    // Functions that are not members of a class are always public.
+    pub visibility: Visibility,
+}
+
+// Constructors and methods belong to a class, and are given an object of it (`this`) as a hidden
+// first argument. A constructor is given the new object it initializes, and returns it.
+#[derive(Clone, PartialEq)]
+pub enum FunctionKind {
+    Function,
+    Constructor(String),
+    Method(String),
+}
+
+impl FunctionKind {
+    // The class a constructor or a method belongs to.
+    pub fn class(&self) -> Option<&str> {
+        match self {
+            FunctionKind::Function => None,
+            FunctionKind::Constructor(class) | FunctionKind::Method(class) => Some(class),
+        }
+    }
+
+    // What diagnostics call a function of this kind.
+    fn name(&self) -> &'static str {
+        match self {
+            FunctionKind::Function => "Function",
+            FunctionKind::Constructor(_) => "Constructor",
+            FunctionKind::Method(_) => "Method",
+        }
+    }
+}
+
+// What the analysis knows about a class. A class has every member of its base class, followed by
+// its own.
+pub struct ClassSymbol {
+    pub base: Option<String>,
+    // The fields of the class, in the order they are declared (starting with the ones of the base
+    // class).
+    pub fields: Vec<FieldSymbol>,
+    // The overloads of the methods with each name. A method that overrides a method of the base
+    // class (by taking the same types) replaces it.
+    pub methods: HashMap<String, Vec<FunctionSymbol>>,
+    // Every method of the class, in the order they are called through the vtable of its objects.
+    // A method keeps the place of the method it overrides, so a method is in the same place for a
+    // class and every class that extends it.
+    pub vtable: Vec<FunctionSymbol>,
+}
+
+#[derive(Clone)]
+pub struct FieldSymbol {
+    pub name: String,
+    pub meta_type: TypeMeta,
+    pub visibility: Visibility,
+    // The class that declares the field, which is the only one that can use it if it is private.
+    pub class: String,
+}
+
+// The symbols of the builtin functions that work on lists. They take lists of any type, so they
+// cannot be declared like the other builtin functions, and the code generators emit them inline
+// (with the help of the runtime), since what they do depends on the type of the elements.
+pub const LIST_PUSH: &str = "__objection_list_push";
+pub const LIST_POP: &str = "__objection_list_pop";
+pub const LIST_LEN: &str = "__objection_list_len";
+
+// The names of the builtin functions that are checked by check_list_call. `len` also takes a
+// string, like any other builtin function.
+const LIST_FUNCTIONS: [&str; 3] = ["push", "pop", "len"];
+
+// A variable that is visible in the scope being analyzed.
+struct VariableSymbol {
+    // The span of the name of the variable in its declaration, which is what identifies it.
+    decl: Span,
+    mutable: bool,
+    meta_type: TypeMeta,
+}
+
+// The result of the semantic analysis. The backends use it to find what every identifier refers
+// to and what type every expression has, so they do not have to know the scoping and typing rules
+// of the language.
+#[derive(Default)]
+pub struct SymbolTable {
+    // The functions with each name. There can be more than one (overloads), which differ by the
+    // types of their parameters. The constructors of a class are functions with the name of the
+    // class.
+    functions: HashMap<String, Vec<FunctionSymbol>>,
+    classes: HashMap<String, ClassSymbol>,
+    // The variants of every enum, in the order they are declared (which is their value).
+    enums: HashMap<String, Vec<String>>,
+    // For every identifier that names the variant of an enum (in `Color.RED`), the value of the
+    // variant.
+    variants: HashMap<Span, usize>,
+    // For every identifier that names a function (in a call, or in its definition), the function
+    // it refers to.
+    function_references: HashMap<Span, FunctionSymbol>,
+    // For every identifier that refers to a variable, the span of the name of the variable in its
+    // declaration. The name in a declaration (or a parameter) refers to itself.
+    references: HashMap<Span, Span>,
+    // The type of every expression, and of every declared variable (by the span of its name in
+    // the declaration). No two expressions have the same span.
+    types: HashMap<Span, TypeMeta>,
+}
+
+impl SymbolTable {
+    // Finds the declaration of the variable an identifier refers to. Every identifier in the
+    // program was resolved by the analysis, so this only fails on identifiers from elsewhere.
+    pub fn declaration(&self, ident: &NodeIdent) -> Span {
+        *self
+            .references
+            .get(&ident.span)
+            .expect("Error, identifier was not resolved")
+    }
+
+    // Finds the function a call (or a definition) refers to, by the identifier that names it.
+    pub fn function(&self, ident: &NodeIdent) -> &FunctionSymbol {
+        self.function_references
+            .get(&ident.span)
+            .expect("Error, function was not resolved")
+    }
+
+    // Finds a class by its name. Every class used in the program was checked by the analysis.
+    pub fn class(&self, name: &str) -> &ClassSymbol {
+        self.classes
+            .get(name)
+            .expect("Error, class was not declared")
+    }
+
+    // The value of the variant of an enum, by the identifier that names it, if it names one.
+    pub fn variant(&self, ident: &NodeIdent) -> Option<usize> {
+        self.variants.get(&ident.span).copied()
+    }
+
+    // The type of an expression (its meta type).
+    pub fn meta_type(&self, expr: &NodeExpr) -> &TypeMeta {
+        self.types
+            .get(&expr.span())
+            .expect("Error, expression was not typed")
+    }
+
+    // The type a variable is declared with.
+    pub fn variable_type(&self, ident: &NodeIdent) -> &TypeMeta {
+        self.types
+            .get(&self.declaration(ident))
+            .expect("Error, variable was not typed")
+    }
+}
+
+// Resolves every identifier in the program and checks the rules that do not depend on the target:
+// variables and functions must be declared (once) before they are used, constants cannot be
+// reassigned, calls must have the right number of arguments, functions must return on every path,
+// constructors must initialize every field of their class and there must be a `main(): int`
+// function to start the program from. It also infers the type of
+// every expression (its meta type), and checks that it is the type the expression is used as.
+pub fn analyze(prog: &NodeProg) -> Result<SymbolTable, Vec<Diagnostic>> {
+    let mut analyzer = Analyzer {
+        symbols: SymbolTable::default(),
+        scopes: Vec::new(),
+        r_type: None,
+        class: None,
+        diagnostics: Vec::new(),
+    };
+    analyzer.analyze_prog(prog);
+    if analyzer.diagnostics.is_empty() {
+        Ok(analyzer.symbols)
+    } else {
+        Err(analyzer.diagnostics)
+    }
+}
+
+struct Analyzer {
+    symbols: SymbolTable,
+    // The variables of every scope we are in (the innermost scope is the last one).
+    scopes: Vec<HashMap<String, VariableSymbol>>,
+    // The return type of the function being analyzed. Constructors do not have one.
+    r_type: Option<TypeMeta>,
+    // The class whose constructor (or method) is being analyzed, which is the type of `this`. Only
+    // the code of a class can use its private members.
+    class: Option<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Analyzer {
+    fn analyze_prog(&mut self, prog: &NodeProg) {
+        // Functions and classes can be used before they are defined, so they are all declared
+        // first, after the builtin functions. The names of the classes are declared before their
+        // fields, which can be objects of any class.
+        for (name, function) in builtin_functions() {
+            self.symbols
+                .functions
+                .entry(name.to_string())
+                .or_default()
+                .push(function);
+        }
+        let classes: Vec<&NodeClass> = prog
+            .classes
+            .iter()
+            .filter(|class| self.declare_class(class))
+            .collect();
+        // Enums are types too, so they are declared before anything uses them.
+        for node_enum in prog.enums.iter() {
+            self.declare_enum(node_enum);
+        }
+        // A class starts with the members of its base class, so the base is defined first.
+        let bases = self.resolve_bases(&classes);
+        let mut ordered = classes.clone();
+        ordered.sort_by_key(|class| ancestors(&class.ident.name, &bases));
+        for class in ordered {
+            let base = bases.get(&class.ident.name).cloned();
+            self.define_class(class, base);
+        }
+        for func in prog.functions.iter() {
+            // Functions with the same name are overloads, which must take different types. `main`
+            // cannot be overloaded, since it is what the program starts from.
+            let params = self.param_types(func);
+            let builtin = is_builtin(&func.ident.name);
+            let class = self.symbols.classes.contains_key(&func.ident.name);
+            let overloads = self.symbols.functions.get(&func.ident.name);
+            let main = func.ident.name == "main" && overloads.is_some();
+            let same = has_params(overloads, &params);
+            if builtin || class || main || same {
+                let note = if builtin {
+                    format!("`{}` is a builtin function", func.ident.name)
+                } else if class {
+                    format!("`{}` is a class", func.ident.name)
+                } else if same {
+                    format!("both functions take {}", describe_params(&params))
+                } else {
+                    "`main` cannot be overloaded".to_string()
+                };
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("Function {} is already defined.", func.ident.name),
+                        func.ident.span,
+                    )
+                    .with_note(note)
+                    .with_help("give one of the functions a different name".to_string()),
+                );
+                continue;
+            }
+            for param in func.params.iter() {
+                self.check_type(&param.p_type);
+            }
+            self.check_type(&func.r_type);
+            let function = FunctionSymbol {
+                symbol: mangle(None, &func.ident.name, &params),
+                params,
+                r_type: self.resolve_type(&func.r_type),
+                kind: FunctionKind::Function,
+                visibility: Visibility::Public,
+            };
+            self.symbols
+                .function_references
+                .insert(func.ident.span, function.clone());
+            self.symbols
+                .functions
+                .entry(func.ident.name.clone())
+                .or_default()
+                .push(function);
+        }
+        for class in classes.iter() {
+            for constructor in class.constructors.iter() {
+                self.analyze_constructor(class, &constructor.func);
+            }
+            self.class = Some(class.ident.name.clone());
+            for method in class.methods.iter() {
+                self.analyze_func(&method.func);
+            }
+            self.class = None;
+        }
+        for func in prog.functions.iter() {
+            self.analyze_func(func);
+        }
+        self.check_main(prog);
+    }
+
+    // Declares the name of a class, and returns whether it was declared (it is not if the name is
+    // already used).
+    fn declare_class(&mut self, class: &NodeClass) -> bool {
+        let builtin = is_builtin(&class.ident.name);
+        if builtin || self.symbols.classes.contains_key(&class.ident.name) {
+            let mut diagnostic = Diagnostic::error(
+                format!("Class {} is already defined.", class.ident.name),
+                class.ident.span,
+            );
+            if builtin {
+                diagnostic =
+                    diagnostic.with_note(format!("`{}` is a builtin function", class.ident.name));
+            }
+            self.diagnostics
+                .push(diagnostic.with_help("give one of the classes a different name".to_string()));
+            return false;
+        }
+        let class_symbol = ClassSymbol {
+            base: None,
+            fields: Vec::new(),
+            methods: HashMap::new(),
+            vtable: Vec::new(),
+        };
+        self.symbols
+            .classes
+            .insert(class.ident.name.clone(), class_symbol);
+        true
+    }
+
+    // Declares an enum with its variants, unless its name is already used.
+    fn declare_enum(&mut self, node_enum: &NodeEnum) {
+        let name = &node_enum.ident.name;
+        let builtin = is_builtin(name);
+        let class = self.symbols.classes.contains_key(name);
+        if builtin || class || self.symbols.enums.contains_key(name) {
+            let mut diagnostic = Diagnostic::error(
+                format!("Enum {} is already defined.", name),
+                node_enum.ident.span,
+            );
+            if builtin {
+                diagnostic = diagnostic.with_note(format!("`{}` is a builtin function", name));
+            } else if class {
+                diagnostic = diagnostic.with_note(format!("`{}` is a class", name));
+            }
+            self.diagnostics
+                .push(diagnostic.with_help("give one of the types a different name".to_string()));
+            return;
+        }
+        let mut variants: Vec<String> = Vec::new();
+        for variant in node_enum.variants.iter() {
+            if variants.contains(&variant.name) {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("Variant {} is already defined in {}.", variant.name, name),
+                        variant.span,
+                    )
+                    .with_help("give one of the variants a different name".to_string()),
+                );
+                continue;
+            }
+            variants.push(variant.name.clone());
+        }
+        self.symbols.enums.insert(name.clone(), variants);
+    }
+
+    // Finds the base class of every class that extends one, and reports the ones that do not
+    // exist or that would make a class extend itself (which are then ignored).
+    fn resolve_bases(&mut self, classes: &[&NodeClass]) -> HashMap<String, String> {
+        let mut bases: HashMap<String, String> = HashMap::new();
+        for class in classes.iter() {
+            let Some(base) = &class.base else {
+                continue;
+            };
+            if !self.symbols.classes.contains_key(&base.name) {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("Class {} not found.", base.name), base.span)
+                        .with_help("classes are declared as `class Name { ... }`".to_string()),
+                );
+                continue;
+            }
+            // Only the bases that do not close a cycle are added, so following the bases of any
+            // class always ends.
+            let mut ancestor = Some(&base.name);
+            while let Some(name) = ancestor {
+                if *name == class.ident.name {
+                    break;
+                }
+                ancestor = bases.get(name);
+            }
+            if ancestor.is_some() {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("Class {} extends itself.", class.ident.name),
+                        base.span,
+                    )
+                    .with_note(format!(
+                        "{} is {} or extends it, through other classes",
+                        base.name, class.ident.name
+                    )),
+                );
+                continue;
+            }
+            bases.insert(class.ident.name.clone(), base.name.clone());
+        }
+        bases
+    }
+
+    // Declares the fields, the constructors and the methods of a class, after the ones it gets
+    // from its base class (which is already defined). The constructors are functions with the name
+    // of the class, which return a new object of it. Like functions, methods and constructors can
+    // be overloaded, and a method overrides the method of the base class that takes the same types.
+    fn define_class(&mut self, class: &NodeClass, base: Option<String>) {
+        let (mut fields, mut methods, mut vtable) = match &base {
+            Some(base) => {
+                let base = self.symbols.class(base);
+                (
+                    base.fields.clone(),
+                    base.methods.clone(),
+                    base.vtable.clone(),
+                )
+            }
+            None => (Vec::new(), HashMap::new(), Vec::new()),
+        };
+        for field in class.fields.iter() {
+            self.check_type(&field.f_type);
+            if let Some(other) = fields.iter().find(|other| other.name == field.ident.name) {
+                let mut diagnostic = Diagnostic::error(
+                    format!(
+                        "Field {} is already defined in {}.",
+                        field.ident.name, class.ident.name
+                    ),
+                    field.ident.span,
+                );
+                if other.class != class.ident.name {
+                    diagnostic = diagnostic.with_note(format!(
+                        "`{}` is a field of {}",
+                        field.ident.name, other.class
+                    ));
+                }
+                self.diagnostics.push(
+                    diagnostic.with_help("give one of the fields a different name".to_string()),
+                );
+                continue;
+            }
+            // The methods of the class itself come later, and are checked against the fields.
+            if let Some(overloads) = methods.get(&field.ident.name) {
+                let base = overloads[0]
+                    .kind
+                    .class()
+                    .expect("Error, method without a class");
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "Field {} is already defined in {}.",
+                            field.ident.name, class.ident.name
+                        ),
+                        field.ident.span,
+                    )
+                    .with_note(format!("`{}` is a method of {}", field.ident.name, base))
+                    .with_help("give one of the members a different name".to_string()),
+                );
+                continue;
+            }
+            fields.push(FieldSymbol {
+                name: field.ident.name.clone(),
+                meta_type: self.resolve_type(&field.f_type),
+                visibility: field.visibility,
+                class: class.ident.name.clone(),
+            });
+        }
+        for method in class.methods.iter() {
+            let func = &method.func;
+            let params = self.param_types(func);
+            let field = fields.iter().any(|field| field.name == func.ident.name);
+            // The method of the base class (if any) that this one overrides.
+            let overridden = methods.get(&func.ident.name).and_then(|overloads| {
+                overloads
+                    .iter()
+                    .position(|other| other.params == params)
+                    .map(|idx| (idx, overloads[idx].clone()))
+            });
+            let same = overridden
+                .as_ref()
+                .is_some_and(|(_, other)| other.kind.class() == Some(&class.ident.name));
+            if field || same {
+                let note = if field {
+                    format!("`{}` is a field", func.ident.name)
+                } else {
+                    format!("both methods take {}", describe_params(&params))
+                };
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "Method {} is already defined in {}.",
+                            func.ident.name, class.ident.name
+                        ),
+                        func.ident.span,
+                    )
+                    .with_note(note)
+                    .with_help("give one of the members a different name".to_string()),
+                );
+                continue;
+            }
+            for param in func.params.iter() {
+                self.check_type(&param.p_type);
+            }
+            self.check_type(&func.r_type);
+            let function = FunctionSymbol {
+                symbol: mangle(Some(&class.ident.name), &func.ident.name, &params),
+                params,
+                r_type: self.resolve_type(&func.r_type),
+                kind: FunctionKind::Method(class.ident.name.clone()),
+                visibility: method.visibility,
+            };
+            self.symbols
+                .function_references
+                .insert(func.ident.span, function.clone());
+            let overloads = methods.entry(func.ident.name.clone()).or_default();
+            match overridden {
+                // An override is called through the same place in the vtable, so it has to
+                // return the same type (and be just as visible) for the callers that only know the
+                // base class.
+                Some((idx, other)) => {
+                    let base = other.kind.class().expect("Error, method without a class");
+                    if other.visibility != function.visibility {
+                        self.diagnostics.push(
+                            Diagnostic::error(
+                                format!(
+                                    "Method {} of {} cannot change the visibility of the method it overrides.",
+                                    func.ident.name, class.ident.name
+                                ),
+                                func.ident.span,
+                            )
+                            .with_note(format!(
+                                "`{}` is declared with `{:?}` in {}",
+                                func.ident.name, other.visibility, base
+                            ))
+                            .with_help(format!(
+                                "declare the method with `{:?}`",
+                                other.visibility
+                            )),
+                        );
+                    }
+                    if other.r_type != function.r_type {
+                        self.diagnostics.push(
+                            Diagnostic::error(
+                                format!(
+                                    "Mismatched types: expected {:?}, found {:?}.",
+                                    other.r_type, function.r_type
+                                ),
+                                func.r_type.span,
+                            )
+                            .with_note(format!(
+                                "{} overrides the method of {}, so it must return the same type",
+                                func.ident.name, base
+                            )),
+                        );
+                    }
+                    let slot = vtable
+                        .iter()
+                        .position(|entry| entry.symbol == other.symbol)
+                        .expect("Error, inherited method is not in the vtable");
+                    vtable[slot] = function.clone();
+                    overloads[idx] = function;
+                }
+                None => {
+                    vtable.push(function.clone());
+                    overloads.push(function);
+                }
+            }
+        }
+        let class_symbol = ClassSymbol {
+            base,
+            fields,
+            methods,
+            vtable,
+        };
+        self.symbols
+            .classes
+            .insert(class.ident.name.clone(), class_symbol);
+        let class_type = TypeMeta::Class(class.ident.name.clone());
+        for constructor in class.constructors.iter() {
+            let func = &constructor.func;
+            let params = self.param_types(func);
+            if has_params(self.symbols.functions.get(&class.ident.name), &params) {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("Constructor {} is already defined.", class.ident.name),
+                        func.ident.span,
+                    )
+                    .with_note(format!(
+                        "both constructors take {}",
+                        describe_params(&params)
+                    ))
+                    .with_help("give the constructors different parameters".to_string()),
+                );
+                continue;
+            }
+            for param in func.params.iter() {
+                self.check_type(&param.p_type);
+            }
+            if func.r_type.meta != class_type {
+                let found = &func.r_type.meta;
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "Mismatched types: expected {:?}, found {:?}.",
+                            class_type, found
+                        ),
+                        func.r_type.span,
+                    )
+                    .with_note("a constructor returns the object it initializes".to_string()),
+                );
+            }
+            let function = FunctionSymbol {
+                symbol: mangle(Some(&class.ident.name), &class.ident.name, &params),
+                params,
+                r_type: class_type.clone(),
+                kind: FunctionKind::Constructor(class.ident.name.clone()),
+                visibility: constructor.visibility,
+            };
+            self.symbols
+                .function_references
+                .insert(func.ident.span, function.clone());
+            self.symbols
+                .functions
+                .entry(class.ident.name.clone())
+                .or_default()
+                .push(function);
+        }
+    }
+
+    // Reports a type that uses a class (or an enum) that does not exist, and returns whether the
+    // type exists.
+    fn check_type(&mut self, node_type: &NodeType) -> bool {
+        let Some(name) = class_name(&node_type.meta) else {
+            return true;
+        };
+        let exists =
+            self.symbols.classes.contains_key(name) || self.symbols.enums.contains_key(name);
+        if !exists {
+            self.diagnostics.push(
+                Diagnostic::error(format!("Class {} not found.", name), node_type.span)
+                    .with_help("classes are declared as `class Name { ... }`".to_string()),
+            );
+        }
+        exists
+    }
+
+    // The type a node stands for. The parser cannot tell an enum from a class, so it makes every
+    // type with a name a class, and the ones that name an enum are turned into enums here.
+    fn resolve_type(&self, node_type: &NodeType) -> TypeMeta {
+        resolve_enums(&node_type.meta, &self.symbols.enums)
+    }
+
+    // The types of the parameters of a function.
+    fn param_types(&self, func: &NodeFunc) -> Vec<TypeMeta> {
+        func.params
+            .iter()
+            .map(|param| self.resolve_type(&param.p_type))
+            .collect()
+    }
+
+    // The program starts by calling main without any arguments, and exits with the value it
+    // returns.
+    fn check_main(&mut self, prog: &NodeProg) {
+        let main = match prog.functions.iter().find(|func| func.ident.name == "main") {
+            Some(main) => main,
+            None => {
+                self.diagnostics.push(
+                    Diagnostic::error("Function main not found.".to_string(), Span::new(0, 0))
+                        .with_note("every program starts by calling `main`".to_string())
+                        .with_help("add `main(): int = { return 0; }`".to_string()),
+                );
+                return;
+            }
+        };
+        if !main.params.is_empty() {
+            let span = main.params[0]
+                .span
+                .to(main.params[main.params.len() - 1].span);
+            self.diagnostics.push(
+                Diagnostic::error("Function main cannot take parameters.".to_string(), span)
+                    .with_help("remove the parameters of `main`".to_string()),
+            );
+        }
+        if !matches!(main.r_type.meta, TypeMeta::Primitive(PrimitiveType::Int)) {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    "Function main must return an int.".to_string(),
+                    main.r_type.span,
+                )
+                .with_note(
+                    "the value returned by main is the exit code of the program".to_string(),
+                ),
+            );
+        }
+    }
+
+    fn analyze_func(&mut self, func: &NodeFunc) {
+        // The parameters and the variables declared directly in the body share the same scope,
+        // so a variable cannot be declared with the name of a parameter.
+        self.scopes.push(HashMap::new());
+        self.r_type = Some(self.resolve_type(&func.r_type));
+        for param in func.params.iter() {
+            let meta_type = self.resolve_type(&param.p_type);
+            self.declare(&param.ident, true, &meta_type);
+        }
+        for stmt in func.block.stmts.iter() {
+            self.analyze_stmt(stmt);
+        }
+        self.r_type = None;
+        self.scopes.pop();
+        if !block_returns(&func.block) {
+            let kind = match self.class {
+                Some(_) => "Method",
+                None => "Function",
+            };
+            self.diagnostics.push(
+                Diagnostic::error(
+                    format!(
+                        "{} {} does not return a value on every path.",
+                        kind, func.ident.name
+                    ),
+                    func.r_type.span,
+                )
+                .with_note("the end of its body can be reached without a `return`".to_string())
+                .with_help("add a `return` at the end of the body".to_string()),
+            );
+        }
+    }
+
+    // A constructor is analyzed like a function that does not return anything, where `this` is the
+    // object being initialized.
+    fn analyze_constructor(&mut self, class: &NodeClass, func: &NodeFunc) {
+        self.scopes.push(HashMap::new());
+        self.class = Some(class.ident.name.clone());
+        for param in func.params.iter() {
+            let meta_type = self.resolve_type(&param.p_type);
+            self.declare(&param.ident, true, &meta_type);
+        }
+        for stmt in func.block.stmts.iter() {
+            self.analyze_stmt(stmt);
+        }
+        self.class = None;
+        self.scopes.pop();
+        // A field has to be assigned directly in the body (not in an if statement or a loop), so
+        // that it is always initialized. The fields of the base class can also be initialized all
+        // at once, by calling a constructor of the base class directly in the body.
+        let calls_super = func
+            .block
+            .stmts
+            .iter()
+            .any(|stmt| matches!(stmt, NodeStmt::Expr(NodeExpr::SuperCall(..), _)));
+        let fields = self.symbols.class(&class.ident.name).fields.clone();
+        for field in fields.iter() {
+            let inherited = field.class != class.ident.name;
+            let initialized = (inherited && calls_super)
+                || func.block.stmts.iter().any(|stmt| {
+                    matches!(
+                        stmt,
+                        NodeStmt::MemberAssign(NodeExpr::This(_), ident, _, _)
+                            if ident.name == field.name
+                    )
+                });
+            if !initialized {
+                let help = if inherited {
+                    format!(
+                        "call a constructor of {} in the body of the constructor, e.g. `super(...);`",
+                        field.class
+                    )
+                } else {
+                    format!(
+                        "assign it in the body of the constructor, e.g. `this.{} = ...;`",
+                        field.name
+                    )
+                };
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "Field {} of {} is not initialized by the constructor.",
+                            field.name, class.ident.name
+                        ),
+                        func.ident.span,
+                    )
+                    .with_help(help),
+                );
+            }
+        }
+    }
+
+    fn analyze_block(&mut self, block: &NodeBlock) {
+        self.scopes.push(HashMap::new());
+        for stmt in block.stmts.iter() {
+            self.analyze_stmt(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn analyze_stmt(&mut self, stmt: &NodeStmt) {
+        match stmt {
+            NodeStmt::Return(expr, span) => {
+                let Some(r_type) = self.r_type.clone() else {
+                    self.analyze_expr(expr);
+                    self.diagnostics.push(
+                        Diagnostic::error("Constructors cannot return a value.".to_string(), *span)
+                            .with_note(
+                                "a constructor returns the object it initializes".to_string(),
+                            )
+                            .with_help("remove the `return`".to_string()),
+                    );
+                    return;
+                };
+                let note = format!("the function returns {:?}", r_type);
+                self.expect_type(expr, &r_type, note);
+            }
+            NodeStmt::Assign(ident, a_type, expr, mutable, _) => {
+                // The value is analyzed first, since the variable cannot be used in its own
+                // declaration.
+                let meta_type = self.resolve_type(a_type);
+                let note = format!("{} is declared as {:?}", ident.name, meta_type);
+                if self.check_type(a_type) {
+                    self.expect_type(expr, &meta_type, note);
+                } else {
+                    self.analyze_expr(expr);
+                }
+                self.declare(ident, *mutable, &meta_type);
+            }
+            NodeStmt::Reassign(ident, expr, _) => {
+                let variable = self
+                    .resolve(ident)
+                    .map(|variable| (variable.mutable, variable.meta_type.clone()));
+                let Some((mutable, meta_type)) = variable else {
+                    self.analyze_expr(expr);
+                    return;
+                };
+                let note = format!("{} is declared as {:?}", ident.name, meta_type);
+                self.expect_type(expr, &meta_type, note);
+                if !mutable {
+                    let message = format!("Cannot reassign the constant {}.", ident.name);
+                    self.report_constant(ident, message);
+                }
+            }
+            NodeStmt::IndexAssign(array, index, expr, _) => {
+                match self.check_index(array, index) {
+                    Some(element_type) => {
+                        let note =
+                            format!("the elements of the array are of type {:?}", element_type);
+                        self.expect_type(expr, &element_type, note);
+                    }
+                    None => {
+                        self.analyze_expr(expr);
+                    }
+                }
+                self.check_modifiable(array);
+            }
+            NodeStmt::MemberAssign(object, field, expr, _) => {
+                if let Some(name) = self.enum_name(object) {
+                    self.analyze_expr(expr);
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!("Cannot assign to {}.{}.", name, field.name),
+                            object.span().to(field.span),
+                        )
+                        .with_note("the variants of an enum are constants".to_string()),
+                    );
+                    return;
+                }
+                match self.check_member(object, field) {
+                    Some(field_type) => {
+                        let note = format!("{} is declared as {:?}", field.name, field_type);
+                        self.expect_type(expr, &field_type, note);
+                    }
+                    None => {
+                        self.analyze_expr(expr);
+                    }
+                }
+                self.check_modifiable(object);
+            }
+            NodeStmt::If(cond, then_block, else_block, _) => {
+                let note = "the condition of an if statement must be of type bool".to_string();
+                self.expect_type(cond, &TypeMeta::Primitive(PrimitiveType::Bool), note);
+                self.analyze_block(then_block);
+                if let Some(else_block) = else_block {
+                    self.analyze_block(else_block);
+                }
+            }
+            NodeStmt::While(cond, body, _) => {
+                let note = "the condition of a while loop must be of type bool".to_string();
+                self.expect_type(cond, &TypeMeta::Primitive(PrimitiveType::Bool), note);
+                self.analyze_block(body);
+            }
+            NodeStmt::Break(_) | NodeStmt::Continue(_) => {}
+            // The value of the expression (if it has one) is thrown away.
+            NodeStmt::Expr(expr, _) => {
+                self.analyze_expr(expr);
+            }
+        }
+    }
+
+    // Infers the type of an expression, and records it in the symbol table. It is None when the
+    // type cannot be known because of an error that was already reported, so that one error does
+    // not cause many others.
+    fn analyze_expr(&mut self, expr: &NodeExpr) -> Option<TypeMeta> {
+        let meta_type = self.infer_expr(expr)?;
+        self.symbols.types.insert(expr.span(), meta_type.clone());
+        Some(meta_type)
+    }
+
+    fn infer_expr(&mut self, expr: &NodeExpr) -> Option<TypeMeta> {
+        match expr {
+            NodeExpr::Literal(literal, _) => Some(match literal {
+                NodeLiteral::IntLit(_) => TypeMeta::Primitive(PrimitiveType::Int),
+                NodeLiteral::FloatLit(_) => TypeMeta::Primitive(PrimitiveType::Float),
+                NodeLiteral::BoolLit(_) => TypeMeta::Primitive(PrimitiveType::Bool),
+                NodeLiteral::CharLit(_) => TypeMeta::Primitive(PrimitiveType::Char),
+                NodeLiteral::Str(_) => TypeMeta::String,
+            }),
+            NodeExpr::Ident(ident) => self
+                .resolve(ident)
+                .map(|variable| variable.meta_type.clone()),
+            NodeExpr::Unary(op, expr, _) => {
+                let operand = self.expect_operand(expr, format!("{:?}", op), &unary_op_types(*op));
+                match op {
+                    UnaryOp::Not => Some(TypeMeta::Primitive(PrimitiveType::Bool)),
+                    _ => operand,
+                }
+            }
+            NodeExpr::Binary(op @ (BinaryOp::Eq | BinaryOp::Ne), lhs, rhs, _) => {
+                // Any two values of the same type can be compared for equality, except for arrays,
+                // lists and objects.
+                let note = format!("`{:?}` can only be applied to values", op);
+                let lhs_type = self.expect_value(lhs, note);
+                if let Some(TypeMeta::Array(..) | TypeMeta::List(_) | TypeMeta::Class(_)) = lhs_type
+                {
+                    let (kind, parts) = match lhs_type {
+                        Some(TypeMeta::List(_)) => ("Lists", "elements"),
+                        Some(TypeMeta::Class(_)) => ("Objects", "fields"),
+                        _ => ("Arrays", "elements"),
+                    };
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            format!("{} cannot be compared with `{:?}`.", kind, op),
+                            lhs.span(),
+                        )
+                        .with_help(format!("compare their {} one by one instead", parts)),
+                    );
+                    self.analyze_expr(rhs);
+                } else if let Some(lhs_type) = lhs_type {
+                    let note = format!("both sides of `{:?}` must have the same type", op);
+                    self.expect_type(rhs, &lhs_type, note);
+                } else {
+                    self.analyze_expr(rhs);
+                }
+                Some(TypeMeta::Primitive(PrimitiveType::Bool))
+            }
+            NodeExpr::Binary(op, lhs, rhs, _) => {
+                // Both sides must have the same type, which is checked against the left hand side
+                // if it has a type the operator can be applied to.
+                let operands = binary_op_types(*op);
+                let operand = match self.expect_operand(lhs, format!("{:?}", op), &operands) {
+                    Some(lhs_type) => {
+                        let note = format!("both sides of `{:?}` must have the same type", op);
+                        self.expect_type(rhs, &lhs_type, note);
+                        Some(lhs_type)
+                    }
+                    // The right hand side is still analyzed for its own errors.
+                    None => {
+                        self.analyze_expr(rhs);
+                        None
+                    }
+                };
+                match op {
+                    BinaryOp::Mul
+                    | BinaryOp::Div
+                    | BinaryOp::Mod
+                    | BinaryOp::Add
+                    | BinaryOp::Sub => operand,
+                    _ => Some(TypeMeta::Primitive(PrimitiveType::Bool)),
+                }
+            }
+            NodeExpr::Paren(expr, _) => self.analyze_expr(expr),
+            NodeExpr::Call(ident, args, span) => self.check_call(ident, args, *span),
+            // The first element decides the type of the others.
+            NodeExpr::Array(elements, _) => {
+                let note = "the elements of an array must be values".to_string();
+                let element_type = self.expect_value(&elements[0], note);
+                for element in elements[1..].iter() {
+                    match &element_type {
+                        Some(element_type) => {
+                            let note =
+                                "all the elements of an array must have the same type".to_string();
+                            self.expect_type(element, element_type, note);
+                        }
+                        None => {
+                            self.analyze_expr(element);
+                        }
+                    }
+                }
+                Some(TypeMeta::Array(Box::new(element_type?), elements.len()))
+            }
+            NodeExpr::ArrayRepeat(value, count, _) => {
+                let note = "the elements of an array must be values".to_string();
+                let element_type = self.expect_value(value, note)?;
+                Some(TypeMeta::Array(Box::new(element_type), *count))
+            }
+            NodeExpr::Index(array, index, _) => self.check_index(array, index),
+            // An empty list only has a type where a list is expected (see expect_type).
+            NodeExpr::EmptyList(span) => {
+                self.diagnostics.push(
+                    Diagnostic::error("Cannot infer the type of `[]`.".to_string(), *span)
+                        .with_note("`[]` is an empty list of any type".to_string())
+                        .with_help(
+                            "declare a variable with the type of the list first, e.g. `l: [int] = [];`"
+                                .to_string(),
+                        ),
+                );
+                None
+            }
+            NodeExpr::This(span) => match &self.class {
+                Some(class) => Some(TypeMeta::Class(class.clone())),
+                None => {
+                    self.diagnostics.push(
+                        Diagnostic::error("`this` outside of a class.".to_string(), *span)
+                            .with_note(
+                                "`this` can only be used inside a constructor or a method"
+                                    .to_string(),
+                            ),
+                    );
+                    None
+                }
+            },
+            NodeExpr::Member(object, field, _) => self.check_member(object, field),
+            NodeExpr::MethodCall(object, method, args, span) => {
+                self.check_method_call(object, method, args, *span)
+            }
+            NodeExpr::Super(span) => {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        "`super` can only be used to call the base class.".to_string(),
+                        *span,
+                    )
+                    .with_note("`super` is used as `super.method(...)` or `super(...)`".to_string())
+                    .with_help("use `this` to refer to the object itself".to_string()),
+                );
+                None
+            }
+            // `super(...)` initializes the fields of the base class, and does not have a value.
+            NodeExpr::SuperCall(keyword, args, span) => {
+                let base = self.check_super(keyword.span);
+                let constructor = self.class.is_some() && self.r_type.is_none();
+                let Some(base) = base.filter(|_| constructor) else {
+                    for arg in args.iter() {
+                        self.analyze_expr(arg);
+                    }
+                    if self.class.is_some() && !constructor {
+                        self.diagnostics.push(
+                            Diagnostic::error(
+                                "`super(...)` outside of a constructor.".to_string(),
+                                *span,
+                            )
+                            .with_note(
+                                "a method calls the methods of the base class with `super.method(...)`"
+                                    .to_string(),
+                            ),
+                        );
+                    }
+                    return None;
+                };
+                let ident = NodeIdent {
+                    name: base,
+                    span: keyword.span,
+                };
+                self.check_call(&ident, args, *span)?;
+                Some(TypeMeta::Void)
+            }
+        }
+    }
+
+    // Finds the base class that `super` refers to, in the constructor or the method being
+    // analyzed.
+    fn check_super(&mut self, span: Span) -> Option<String> {
+        let Some(class) = self.class.clone() else {
+            self.diagnostics.push(
+                Diagnostic::error("`super` outside of a class.".to_string(), span).with_note(
+                    "`super` can only be used inside a constructor or a method".to_string(),
+                ),
+            );
+            return None;
+        };
+        let base = self.symbols.class(&class).base.clone();
+        if base.is_none() {
+            self.diagnostics.push(
+                Diagnostic::error(format!("Class {} has no base class.", class), span).with_note(
+                    format!(
+                    "`super` refers to the class that {} extends, as in `class {}: Base {{ ... }}`",
+                    class, class
+                ),
+                ),
+            );
+        }
+        base
+    }
+
+    // Checks that a method is called on an object of a class that has it, like a call to a
+    // function, and records which method is called.
+    fn check_method_call(
+        &mut self,
+        object: &NodeExpr,
+        method: &NodeIdent,
+        args: &[NodeExpr],
+        span: Span,
+    ) -> Option<TypeMeta> {
+        let class = match object {
+            // `super` is `this`, as an object of the base class.
+            NodeExpr::Super(span) => {
+                let base = self.check_super(*span);
+                if let Some(base) = &base {
+                    self.symbols
+                        .types
+                        .insert(*span, TypeMeta::Class(base.clone()));
+                }
+                base
+            }
+            _ => match self.analyze_expr(object) {
+                Some(TypeMeta::Class(class)) => Some(class),
+                Some(found) => {
+                    let note = "only objects have methods".to_string();
+                    self.report_mismatch(object, "an object", &found, note);
+                    None
+                }
+                None => None,
+            },
+        };
+        // The class is not found if its name was already reported.
+        let class_symbol = class
+            .as_ref()
+            .and_then(|class| self.symbols.classes.get(class));
+        let Some(class_symbol) = class_symbol else {
+            for arg in args.iter() {
+                self.analyze_expr(arg);
+            }
+            return None;
+        };
+        let class = class.expect("Error, class symbol without a class");
+        let Some(functions) = class_symbol.methods.get(&method.name).cloned() else {
+            for arg in args.iter() {
+                self.analyze_expr(arg);
+            }
+            self.diagnostics.push(Diagnostic::error(
+                format!("Class {} has no method {}.", class, method.name),
+                method.span,
+            ));
+            return None;
+        };
+        self.check_overloads(method, functions, args, span)
+    }
+
+    // Reports a private member (a field, a method or a constructor) that is used outside of its
+    // class.
+    fn check_visibility(
+        &mut self,
+        kind: &str,
+        member: &NodeIdent,
+        class: &str,
+        visibility: Visibility,
+    ) {
+        if visibility == Visibility::Public || self.class.as_deref() == Some(class) {
+            return;
+        }
+        self.diagnostics.push(
+            Diagnostic::error(
+                format!("{} {} of {} is private.", kind, member.name, class),
+                member.span,
+            )
+            .with_note(format!(
+                "private members can only be used inside the class ({})",
+                class
+            ))
+            .with_help("declare the member with `pub` to use it anywhere".to_string()),
+        );
+    }
+
+    // Checks that a field is accessed on an object of a class that has it, and returns the type of
+    // the field.
+    fn check_member(&mut self, object: &NodeExpr, field: &NodeIdent) -> Option<TypeMeta> {
+        if let Some(name) = self.enum_name(object) {
+            return self.check_variant(name, field);
+        }
+        let class = match self.analyze_expr(object)? {
+            TypeMeta::Class(class) => class,
+            found => {
+                let note = "only objects have fields".to_string();
+                self.report_mismatch(object, "an object", &found, note);
+                return None;
+            }
+        };
+        // The class is not found if its name was already reported.
+        let class_symbol = self.symbols.classes.get(&class)?;
+        let field_symbol = class_symbol
+            .fields
+            .iter()
+            .find(|other| other.name == field.name)
+            .cloned();
+        match field_symbol {
+            Some(field_symbol) => {
+                let FieldSymbol {
+                    meta_type,
+                    visibility,
+                    class,
+                    ..
+                } = field_symbol;
+                self.check_visibility("Field", field, &class, visibility);
+                Some(meta_type)
+            }
+            None => {
+                self.diagnostics.push(Diagnostic::error(
+                    format!("Class {} has no field {}.", class, field.name),
+                    field.span,
+                ));
+                None
+            }
+        }
+    }
+
+    // The enum an object names, as `Color` in `Color.RED`. A variable with the name of an enum
+    // hides it.
+    fn enum_name(&self, object: &NodeExpr) -> Option<String> {
+        let NodeExpr::Ident(ident) = object else {
+            return None;
+        };
+        let variable = self
+            .scopes
+            .iter()
+            .any(|scope| scope.contains_key(&ident.name));
+        if variable || !self.symbols.enums.contains_key(&ident.name) {
+            return None;
+        }
+        Some(ident.name.clone())
+    }
+
+    // Checks that an enum has a variant, and records its value.
+    fn check_variant(&mut self, name: String, variant: &NodeIdent) -> Option<TypeMeta> {
+        let variants = &self.symbols.enums[&name];
+        match variants.iter().position(|other| *other == variant.name) {
+            Some(value) => {
+                self.symbols.variants.insert(variant.span, value);
+                Some(TypeMeta::Enum(name))
+            }
+            None => {
+                let mut diagnostic = Diagnostic::error(
+                    format!("Enum {} has no variant {}.", name, variant.name),
+                    variant.span,
+                );
+                if !variants.is_empty() {
+                    diagnostic = diagnostic.with_note(format!("{} is {}", name, or_list(variants)));
+                }
+                self.diagnostics.push(diagnostic);
+                None
+            }
+        }
+    }
+
+    // Checks that an array (or a list) is indexed by an int, and returns the type of its elements.
+    // An index that is a literal is checked against the length of an array right away, other
+    // indices (and every index of a list) are checked when the program runs.
+    fn check_index(&mut self, array: &NodeExpr, index: &NodeExpr) -> Option<TypeMeta> {
+        let array_type = self.analyze_expr(array);
+        let note = "arrays are indexed by an int".to_string();
+        self.expect_type(index, &TypeMeta::Primitive(PrimitiveType::Int), note);
+        let (element_type, len) = match array_type? {
+            TypeMeta::Array(element_type, len) => (*element_type, len),
+            TypeMeta::List(element_type) => return Some(*element_type),
+            found => {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "Mismatched types: expected an array or a list, found {:?}.",
+                            found
+                        ),
+                        array.span(),
+                    )
+                    .with_note("only arrays and lists can be indexed".to_string()),
+                );
+                return None;
+            }
+        };
+        if let NodeExpr::Literal(NodeLiteral::IntLit(value), span) = index {
+            if *value as u64 >= len as u64 {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("Index {} is out of bounds.", value), *span)
+                        .with_note(format!(
+                            "the array is of type {:?}, so the index must be between 0 and {}",
+                            TypeMeta::Array(Box::new(element_type.clone()), len),
+                            len - 1
+                        )),
+                );
+            }
+        }
+        Some(element_type)
+    }
+
+    // Infers the type of an expression, and reports it if it is not the expected one. The note
+    // explains why that type is expected.
+    fn expect_type(&mut self, expr: &NodeExpr, expected: &TypeMeta, note: String) {
+        // An empty list is a list of whatever type is expected.
+        if let (NodeExpr::EmptyList(span), TypeMeta::List(_)) = (expr, expected) {
+            self.symbols.types.insert(*span, expected.clone());
+            return;
+        }
+        match self.analyze_expr(expr) {
+            Some(found) if !self.is_assignable(&found, expected) => {
+                self.report_mismatch(expr, &format!("{:?}", expected), &found, note)
+            }
+            _ => {}
+        }
+    }
+
+    // Whether a value of a type can be used where another type is expected. That is only the same
+    // type, or an object of a class that extends the class that is expected.
+    fn is_assignable(&self, found: &TypeMeta, expected: &TypeMeta) -> bool {
+        let (TypeMeta::Class(found), TypeMeta::Class(expected)) = (found, expected) else {
+            return found == expected;
+        };
+        let mut class = Some(found);
+        while let Some(name) = class {
+            if name == expected {
+                return true;
+            }
+            class = self.symbols.class(name).base.as_ref();
+        }
+        false
+    }
+
+    // Reports an expression whose type is not the expected one (which can be more than one type,
+    // like `string or a list`).
+    fn report_mismatch(&mut self, expr: &NodeExpr, expected: &str, found: &TypeMeta, note: String) {
+        self.diagnostics.push(
+            Diagnostic::error(
+                format!(
+                    "Mismatched types: expected {}, found {:?}.",
+                    expected, found
+                ),
+                expr.span(),
+            )
+            .with_note(note),
+        );
+    }
+
+    // Infers the type of an expression, and reports it if it does not have a value (it is void).
+    // The note explains why a value is expected.
+    fn expect_value(&mut self, expr: &NodeExpr, note: String) -> Option<TypeMeta> {
+        match self.analyze_expr(expr)? {
+            TypeMeta::Void => {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        "Mismatched types: expected a value, found void.".to_string(),
+                        expr.span(),
+                    )
+                    .with_note(note),
+                );
+                None
+            }
+            found => Some(found),
+        }
+    }
+
+    // Infers the type of the operand of an operator, and reports it if the operator cannot be
+    // applied to it. The type is only returned if it is one of the allowed ones.
+    fn expect_operand(
+        &mut self,
+        expr: &NodeExpr,
+        op: String,
+        allowed: &[TypeMeta],
+    ) -> Option<TypeMeta> {
+        let found = self.analyze_expr(expr)?;
+        if allowed.contains(&found) {
+            return Some(found);
+        }
+        let allowed = type_list(allowed);
+        self.diagnostics.push(
+            Diagnostic::error(
+                format!("Mismatched types: expected {}, found {:?}.", allowed, found),
+                expr.span(),
+            )
+            .with_note(format!("`{}` can only be applied to {}", op, allowed)),
+        );
+        None
+    }
+
+    // Declares a variable in the innermost scope.
+    fn declare(&mut self, ident: &NodeIdent, mutable: bool, meta_type: &TypeMeta) {
+        let scope = self.scopes.last_mut().expect("Error, no valid scope found");
+        if scope.contains_key(&ident.name) {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    format!("Variable {} is already declared in this scope.", ident.name),
+                    ident.span,
+                )
+                .with_help(format!(
+                    "use `{} = ...;` to reassign it, or give the new variable a different name",
+                    ident.name
+                )),
+            );
+        }
+        let variable = VariableSymbol {
+            decl: ident.span,
+            mutable,
+            meta_type: meta_type.clone(),
+        };
+        scope.insert(ident.name.clone(), variable);
+        self.symbols.references.insert(ident.span, ident.span);
+        self.symbols.types.insert(ident.span, meta_type.clone());
+    }
+
+    // Reports a change to the elements of a constant array or list (through an index, `push` or
+    // `pop`), or to the fields of a constant object. Only the elements of a variable can be checked, since the value of any other
+    // expression is not a constant.
+    fn check_modifiable(&mut self, array: &NodeExpr) {
+        let Some(ident) = indexed_variable(array) else {
+            return;
+        };
+        let mutable = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&ident.name))
+            .map(|variable| variable.mutable);
+        if mutable == Some(false) {
+            let message = format!("Cannot modify the constant {}.", ident.name);
+            self.report_constant(ident, message);
+        }
+    }
+
+    // Reports a statement that changes a constant (the message says how it changes it).
+    fn report_constant(&mut self, ident: &NodeIdent, message: String) {
+        self.diagnostics.push(
+            Diagnostic::error(message, ident.span)
+                .with_note(format!("{} is declared with `const`", ident.name))
+                .with_help("remove `const` from its declaration to make it mutable".to_string()),
+        );
+    }
+
+    // Finds the variable an identifier refers to, starting from the innermost scope, and records
+    // it in the symbol table.
+    fn resolve(&mut self, ident: &NodeIdent) -> Option<&VariableSymbol> {
+        let variable = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&ident.name));
+        match variable {
+            Some(variable) => {
+                self.symbols.references.insert(ident.span, variable.decl);
+                Some(variable)
+            }
+            None => {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("Variable {} not found.", ident.name), ident.span)
+                        .with_help(format!(
+                            "declare it before using it, e.g. `{}: int = 0;`",
+                            ident.name
+                        )),
+                );
+                None
+            }
+        }
+    }
+
+    // Checks that a called function (or constructor) exists and that one of its overloads takes
+    // the arguments, and records which one is called. The type of the call is the return type of
+    // that overload.
+    fn check_call(&mut self, ident: &NodeIdent, args: &[NodeExpr], span: Span) -> Option<TypeMeta> {
+        if LIST_FUNCTIONS.contains(&ident.name.as_str()) {
+            return self.check_list_call(ident, args, span);
+        }
+        match self.symbols.functions.get(&ident.name).cloned() {
+            Some(functions) => self.check_overloads(ident, functions, args, span),
+            None if self.symbols.classes.contains_key(&ident.name) => {
+                for arg in args.iter() {
+                    self.analyze_expr(arg);
+                }
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!("Class {} has no constructor.", ident.name),
+                        ident.span,
+                    )
+                    .with_help(format!(
+                        "add one to the class, e.g. `pub {}(): {} = {{ ... }}`",
+                        ident.name, ident.name
+                    )),
+                );
+                None
+            }
+            None => {
+                for arg in args.iter() {
+                    self.analyze_expr(arg);
+                }
+                self.diagnostics.push(
+                    Diagnostic::error(format!("Function {} not found.", ident.name), ident.span)
+                        .with_help(
+                            "functions are declared as `name(param: type, ...): type = { ... }`"
+                                .to_string(),
+                        ),
+                );
+                None
+            }
+        }
+    }
+
+    // Checks that a function (or a method) is given the right number of arguments, of the right
+    // types. The type of the call is the return type of the function.
+    fn check_args(
+        &mut self,
+        ident: &NodeIdent,
+        function: FunctionSymbol,
+        args: &[NodeExpr],
+        span: Span,
+    ) -> Option<TypeMeta> {
+        if function.params.len() != args.len() {
+            for arg in args.iter() {
+                self.analyze_expr(arg);
+            }
+            self.diagnostics.push(Diagnostic::error(
+                format!(
+                    "{} {} takes {} argument(s), but {} were given.",
+                    function.kind.name(),
+                    ident.name,
+                    function.params.len(),
+                    args.len()
+                ),
+                span,
+            ));
+            return Some(function.r_type);
+        }
+        for (idx, (arg, param)) in args.iter().zip(function.params.iter()).enumerate() {
+            let note = format!(
+                "parameter {} of {} is of type {:?}",
+                idx + 1,
+                ident.name,
+                param
+            );
+            self.expect_type(arg, param, note);
+        }
+        Some(function.r_type)
+    }
+
+    // Checks a call to `push(list, value)`, `pop(list)` or `len(list)`, whose parameters depend on
+    // the type of the list they are given. `len` takes a string too.
+    fn check_list_call(
+        &mut self,
+        ident: &NodeIdent,
+        args: &[NodeExpr],
+        span: Span,
+    ) -> Option<TypeMeta> {
+        let params = if ident.name == "push" { 2 } else { 1 };
+        if args.len() != params {
+            for arg in args.iter() {
+                self.analyze_expr(arg);
+            }
+            self.diagnostics.push(Diagnostic::error(
+                format!(
+                    "Function {} takes {} argument(s), but {} were given.",
+                    ident.name,
+                    params,
+                    args.len()
+                ),
+                span,
+            ));
+            return None;
+        }
+        let list_type = self.analyze_expr(&args[0]);
+        let element_type = match list_type {
+            Some(TypeMeta::List(element_type)) => Some(*element_type),
+            Some(TypeMeta::String) if ident.name == "len" => {
+                let function = self.symbols.functions["len"][0].clone();
+                self.symbols
+                    .function_references
+                    .insert(ident.span, function.clone());
+                return Some(function.r_type);
+            }
+            Some(found) => {
+                let (expected, kind) = match ident.name.as_str() {
+                    "len" => ("string or a list", "a string or a list"),
+                    _ => ("a list", "a list"),
+                };
+                let note = format!("parameter 1 of {} is {}", ident.name, kind);
+                self.report_mismatch(&args[0], expected, &found, note);
+                None
+            }
+            None => None,
+        };
+        let Some(element_type) = element_type else {
+            // The value pushed is still analyzed for its own errors.
+            if ident.name == "push" {
+                self.analyze_expr(&args[1]);
+            }
+            return None;
+        };
+        let list_type = TypeMeta::List(Box::new(element_type.clone()));
+        let (params, r_type, symbol) = match ident.name.as_str() {
+            "push" => {
+                let note = format!("the elements of the list are of type {:?}", element_type);
+                self.expect_type(&args[1], &element_type, note);
+                self.check_modifiable(&args[0]);
+                (vec![list_type, element_type], TypeMeta::Void, LIST_PUSH)
+            }
+            "pop" => {
+                self.check_modifiable(&args[0]);
+                (vec![list_type], element_type, LIST_POP)
+            }
+            _ => (
+                vec![list_type],
+                TypeMeta::Primitive(PrimitiveType::Int),
+                LIST_LEN,
+            ),
+        };
+        let function = FunctionSymbol {
+            params,
+            r_type: r_type.clone(),
+            symbol: symbol.to_string(),
+            kind: FunctionKind::Function,
+            visibility: Visibility::Public,
+        };
+        self.symbols
+            .function_references
+            .insert(ident.span, function);
+        Some(r_type)
+    }
+
+    // Checks a call to a function, a constructor or a method with the given overloads, and records
+    // which one is called. The type of the call is the return type of that overload.
+    fn check_overloads(
+        &mut self,
+        ident: &NodeIdent,
+        functions: Vec<FunctionSymbol>,
+        args: &[NodeExpr],
+        span: Span,
+    ) -> Option<TypeMeta> {
+        // Without overloads, every argument is checked against its parameter, so that a mismatch
+        // is reported at the argument.
+        let overloaded = functions.len() > 1;
+        let function = match overloaded {
+            true => self.pick_overload(ident, &functions, args, span)?,
+            false => functions[0].clone(),
+        };
+        self.symbols
+            .function_references
+            .insert(ident.span, function.clone());
+        if let Some(class) = function.kind.class() {
+            self.check_visibility(function.kind.name(), ident, class, function.visibility);
+        }
+        match overloaded {
+            true => Some(function.r_type),
+            false => self.check_args(ident, function, args, span),
+        }
+    }
+
+    // Picks the overload whose parameters have the types of the arguments. If there is none, an
+    // object can also be given to a parameter of a class it extends. An empty list can be a list of
+    // any type, so it is only typed once the overload is picked, and more than one overload can
+    // take it.
+    fn pick_overload(
+        &mut self,
+        ident: &NodeIdent,
+        functions: &[FunctionSymbol],
+        args: &[NodeExpr],
+        span: Span,
+    ) -> Option<FunctionSymbol> {
+        let arg_types: Vec<Option<Option<TypeMeta>>> = args
+            .iter()
+            .map(|arg| match arg {
+                NodeExpr::EmptyList(_) => Some(None),
+                _ => self.analyze_expr(arg).map(Some),
+            })
+            .collect();
+        // The type of an argument is unknown if it had an error, which was already reported.
+        let arg_types: Vec<Option<TypeMeta>> = arg_types.into_iter().collect::<Option<_>>()?;
+        let takes = |function: &FunctionSymbol, exact: bool| {
+            function.params.len() == arg_types.len()
+                && function
+                    .params
+                    .iter()
+                    .zip(arg_types.iter())
+                    .all(|(param, arg_type)| match arg_type {
+                        Some(arg_type) if exact => arg_type == param,
+                        Some(arg_type) => self.is_assignable(arg_type, param),
+                        None => matches!(param, TypeMeta::List(_)),
+                    })
+        };
+        let mut matches: Vec<&FunctionSymbol> = functions
+            .iter()
+            .filter(|function| takes(function, true))
+            .collect();
+        if matches.is_empty() {
+            matches = functions
+                .iter()
+                .filter(|function| takes(function, false))
+                .collect();
+        }
+        let arg_names: Vec<String> = arg_types
+            .iter()
+            .map(|arg_type| match arg_type {
+                Some(arg_type) => format!("{:?}", arg_type),
+                None => "[]".to_string(),
+            })
+            .collect();
+        let kind = functions[0].kind.name();
+        match matches[..] {
+            [function] => {
+                for (arg, param) in args.iter().zip(function.params.iter()) {
+                    if let NodeExpr::EmptyList(span) = arg {
+                        self.symbols.types.insert(*span, param.clone());
+                    }
+                }
+                Some(function.clone())
+            }
+            [] => {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        format!(
+                            "{} {} cannot take ({}).",
+                            kind,
+                            ident.name,
+                            arg_names.join(", ")
+                        ),
+                        span,
+                    )
+                    .with_note(format!(
+                        "{} can take {}",
+                        ident.name,
+                        overload_list(functions.iter())
+                    )),
+                );
+                None
+            }
+            _ => {
+                let mut diagnostic = Diagnostic::error(
+                    format!(
+                        "{} {} can take ({}) in more than one way.",
+                        kind,
+                        ident.name,
+                        arg_names.join(", ")
+                    ),
+                    span,
+                )
+                .with_note(format!(
+                    "{} can take {}",
+                    ident.name,
+                    overload_list(matches.into_iter())
+                ));
+                // Otherwise, an object is of a class that more than one overload takes a base of.
+                if arg_types.contains(&None) {
+                    diagnostic = diagnostic.with_help(
+                        "give `[]` a type by declaring a variable for it first, e.g. `l: [int] = [];`"
+                            .to_string(),
+                    );
+                }
+                self.diagnostics.push(diagnostic);
+                None
+            }
+        }
+    }
+}
+
+// Whether running a block never gets past its end. The statements after one that never gets past
+// its end are never run, so they do not matter.
+fn block_returns(block: &NodeBlock) -> bool {
+    block.stmts.iter().any(|stmt| match stmt {
+        NodeStmt::Return(_, _) => true,
+        NodeStmt::If(_, then_block, Some(else_block), _) => {
+            block_returns(then_block) && block_returns(else_block)
+        }
+        // A loop whose condition is always true can only be left through a `break`.
+        NodeStmt::While(cond, body, _) => is_always_true(cond) && !block_breaks(body),
+        _ => false,
+    })
+}
+
+// Whether a block contains a `break` that exits the loop the block is the body of.
+fn block_breaks(block: &NodeBlock) -> bool {
+    block.stmts.iter().any(|stmt| match stmt {
+        NodeStmt::Break(_) => true,
+        NodeStmt::If(_, then_block, else_block, _) => {
+            block_breaks(then_block) || else_block.as_ref().is_some_and(block_breaks)
+        }
+        // A `break` in a nested loop only exits that loop.
+        _ => false,
+    })
+}
+
+// The variable an array (or an object) comes from, when it is a variable or (nested) elements or
+// fields of one.
+fn indexed_variable(array: &NodeExpr) -> Option<&NodeIdent> {
+    match array {
+        NodeExpr::Ident(ident) => Some(ident),
+        NodeExpr::Index(array, _, _) | NodeExpr::Member(array, _, _) => indexed_variable(array),
+        _ => None,
+    }
+}
+
+// Turns the classes a type uses into enums, where they name one.
+fn resolve_enums(meta_type: &TypeMeta, enums: &HashMap<String, Vec<String>>) -> TypeMeta {
+    match meta_type {
+        TypeMeta::Class(name) if enums.contains_key(name) => TypeMeta::Enum(name.clone()),
+        TypeMeta::Array(element, len) => {
+            TypeMeta::Array(Box::new(resolve_enums(element, enums)), *len)
+        }
+        TypeMeta::List(element) => TypeMeta::List(Box::new(resolve_enums(element, enums))),
+        _ => meta_type.clone(),
+    }
+}
+
+// Whether one of the overloads of a function takes parameters of the same types.
+fn has_params(overloads: Option<&Vec<FunctionSymbol>>, params: &[TypeMeta]) -> bool {
+    overloads.is_some_and(|overloads| overloads.iter().any(|other| other.params == params))
+}
+
+// The number of classes a class extends, directly or through other classes.
+fn ancestors(class: &str, bases: &HashMap<String, String>) -> usize {
+    let mut count = 0;
+    let mut ancestor = bases.get(class);
+    while let Some(name) = ancestor {
+        count += 1;
+        ancestor = bases.get(name);
+    }
+    count
+}
+
+// The class a type uses, if it is an object or has objects as its elements.
+fn class_name(meta_type: &TypeMeta) -> Option<&str> {
+    match meta_type {
+        TypeMeta::Class(name) => Some(name),
+        TypeMeta::Array(element, _) | TypeMeta::List(element) => class_name(element),
+        _ => None,
+    }
+}
+
+// Whether a name is taken by a builtin function.
+fn is_builtin(name: &str) -> bool {
+    LIST_FUNCTIONS.contains(&name) || builtin_functions().iter().any(|(other, _)| *other == name)
+}
+
+// The functions every program can call without defining them. They are provided by the runtime
+// that the code generators emit along with the program.
+fn builtin_functions() -> Vec<(&'static str, FunctionSymbol)> {
+    let mut builtins = vec![(
+        "len",
+        FunctionSymbol {
+            params: vec![TypeMeta::String],
+            r_type: TypeMeta::Primitive(PrimitiveType::Int),
+            symbol: "__objection_len".to_string(),
+            kind: FunctionKind::Function,
+            visibility: Visibility::Public,
+        },
+    )];
+    // `print` writes any value (without a newline) to the standard output.
+    let printable = [
+        TypeMeta::Primitive(PrimitiveType::Int),
+        TypeMeta::Primitive(PrimitiveType::Float),
+        TypeMeta::Primitive(PrimitiveType::Bool),
+        TypeMeta::Primitive(PrimitiveType::Char),
+        TypeMeta::String,
+    ];
+    for meta_type in printable {
+        let symbol = format!("__objection_print_{:?}", meta_type);
+        builtins.push((
+            "print",
+            FunctionSymbol {
+                params: vec![meta_type],
+                r_type: TypeMeta::Void,
+                symbol,
+                kind: FunctionKind::Function,
+                visibility: Visibility::Public,
+            },
+        ));
+    }
+    builtins
+}
+
+// The types a unary operator can be applied to. The result has the type of the operand, except
+// for `!`, whose result is a bool.
+fn unary_op_types(op: UnaryOp) -> Vec<TypeMeta> {
+    let types = match op {
+        UnaryOp::Neg => vec![PrimitiveType::Int, PrimitiveType::Float],
+        UnaryOp::BitNot => vec![PrimitiveType::Int],
+        UnaryOp::Not => vec![PrimitiveType::Bool],
+    };
+    types.into_iter().map(TypeMeta::Primitive).collect()
+}
+
+// The types a binary operator (other than `==` and `!=`, which work on any type) can be applied
+// to. The result of an arithmetic operator has the type of its operands (`+` on strings
+// concatenates them), while the result of the others is a bool.
+fn binary_op_types(op: BinaryOp) -> Vec<TypeMeta> {
+    let int = TypeMeta::Primitive(PrimitiveType::Int);
+    let float = TypeMeta::Primitive(PrimitiveType::Float);
+    match op {
+        BinaryOp::Add => vec![int, float, TypeMeta::String],
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Sub => vec![int, float],
+        BinaryOp::Mod => vec![int],
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            vec![int, float, TypeMeta::Primitive(PrimitiveType::Char)]
+        }
+        BinaryOp::And | BinaryOp::Or => vec![TypeMeta::Primitive(PrimitiveType::Bool)],
+        BinaryOp::Eq | BinaryOp::Ne => unreachable!("Error, {:?} works on any type", op),
+    }
+}
+
+// Lists types for a diagnostic, e.g. `int, float or char`.
+fn type_list(types: &[TypeMeta]) -> String {
+    let names: Vec<String> = types
+        .iter()
+        .map(|meta_type| format!("{:?}", meta_type))
+        .collect();
+    or_list(&names)
+}
+
+// Lists the types of parameters or arguments for a diagnostic, e.g. `int, float`.
+fn type_names(types: &[TypeMeta]) -> String {
+    let names: Vec<String> = types
+        .iter()
+        .map(|meta_type| format!("{:?}", meta_type))
+        .collect();
+    names.join(", ")
+}
+
+// Lists the parameters of overloads for a diagnostic, e.g. `(int), (float) or (string)`.
+fn overload_list<'a>(functions: impl Iterator<Item = &'a FunctionSymbol>) -> String {
+    let names: Vec<String> = functions
+        .map(|function| format!("({})", type_names(&function.params)))
+        .collect();
+    or_list(&names)
+}
+
+// Describes the parameters a function takes for a diagnostic, e.g. `(int, float)`.
+fn describe_params(params: &[TypeMeta]) -> String {
+    match params {
+        [] => "no parameters".to_string(),
+        _ => format!("({})", type_names(params)),
+    }
+}
+
+// Joins the last two names with `or` and the others with commas.
+fn or_list(names: &[String]) -> String {
+    match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+        _ => names.join(""),
+    }
+}
+
+fn is_always_true(expr: &NodeExpr) -> bool {
+    match expr {
+        NodeExpr::Literal(NodeLiteral::BoolLit(val), _) => *val,
+        NodeExpr::Paren(expr, _) => is_always_true(expr),
+        _ => false,
+    }
+}
@@ -21,6 +21,64 @@ fn compile_and_run(prog_path: &Path, exe_path: &Path) -> i32 {
         .expect("Program did not provide an exit code")
 }
 
+fn compile_and_run_with_output(prog_path: &Path, exe_path: &Path) -> (i32, String, String) {
+    // Run our compiler to compile our objection program
+    let compiler_bin = Path::new("target/debug/objection");
+    let process = Command::new(compiler_bin)
+        .arg(prog_path)
+        .status()
+        .expect("Failed to run compiler");
+    assert!(process.success(), "Failed to compile");
+
+    // Run the compiled program, keeping what it prints
+    let output = Command::new(exe_path)
+        .output()
+        .expect("Failed to run compiled program");
+    let exit_code = output
+        .status
+        .code()
+        .expect("Program did not provide an exit code");
+    let stdout = String::from_utf8(output.stdout).expect("Output is not valid UTF-8");
+    let stderr = String::from_utf8(output.stderr).expect("Output is not valid UTF-8");
+    (exit_code, stdout, stderr)
+}
+
+fn compile_and_fail(prog_path: &Path, args: &[&str]) -> String {
+    // Run our compiler on a program that is expected to be rejected
+    let compiler_bin = Path::new("target/debug/objection");
+    let output = Command::new(compiler_bin)
+        .arg(prog_path)
+        .args(args)
+        .output()
+        .expect("Failed to run compiler");
+    assert!(!output.status.success(), "Compiled a program with errors");
+    String::from_utf8(output.stderr).expect("Diagnostics are not valid UTF-8")
+}
+
+fn generate_asm(prog_path: &Path, os: &str, arch: &str) -> String {
+    // Run our compiler for the given target, stopping once the assembly code is generated
+    let compiler_bin = Path::new("target/debug/objection");
+    let output = Command::new(compiler_bin)
+        .env("TARGET_OS", os)
+        .env("TARGET_ARCH", arch)
+        .arg(prog_path)
+        .arg("--asm")
+        .arg("--no-build")
+        .output()
+        .expect("Failed to run compiler");
+    assert!(output.status.success(), "Failed to compile");
+    String::from_utf8(output.stdout).expect("Assembly code is not valid UTF-8")
+}
+
+fn assert_snapshot(prog_path: &Path, os: &str, arch: &str, snapshot_path: &Path) {
+    let asm = generate_asm(prog_path, os, arch);
+    let snapshot = fs::read_to_string(snapshot_path).expect("Failed to read snapshot");
+    assert_eq!(
+        asm, snapshot,
+        "Generated assembly does not match the snapshot"
+    );
+}
+
 #[test]
 fn test_1() {
     // Path of the file to compile
@@ -54,3 +112,1679 @@ fn test_2() {
     // Clean up
     let _ = fs::remove_file(exe_path);
 }
+
+#[test]
+fn test_3() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/3.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/3");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 182,
+        "Program did not exit with the expected code"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_3_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/3.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_3.s"),
+    );
+}
+
+#[test]
+fn test_4() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/4.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/4");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 132,
+        "Program did not exit with the expected code"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_4_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/4.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_4.s"),
+    );
+}
+
+#[test]
+fn test_5() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/5.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/5");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 133,
+        "Program did not exit with the expected code"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_2_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/2.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_2.s"),
+    );
+}
+
+#[test]
+fn test_2_macos_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/2.ob"),
+        "macos",
+        "aarch64",
+        Path::new("tests/snapshots/macos_aarch64_2.s"),
+    );
+}
+
+#[test]
+fn test_errors_1() {
+    let stderr = compile_and_fail(Path::new("ex/errors/1.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Expected the symbol Semi, got Ret instead.
+ --> ex/errors/1.ob:7:5
+  |
+7 |     return my_var;
+  |     ^^^^^^
+  = note: every statement must end with a semicolon
+
+"#,
+        "Compiler did not report the expected diagnostic"
+    );
+}
+
+#[test]
+fn test_errors_2() {
+    let stderr = compile_and_fail(Path::new("ex/errors/2.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Variable my_vra not found.
+ --> ex/errors/2.ob:7:12
+  |
+7 |     return my_vra;
+  |            ^^^^^^
+  = help: declare it before using it, e.g. `my_vra: int = 0;`
+
+"#,
+        "Compiler did not report the expected diagnostic"
+    );
+}
+
+#[test]
+fn test_errors_3() {
+    let stderr = compile_and_fail(Path::new("ex/errors/3.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Unknown character '$'.
+ --> ex/errors/3.ob:6:23
+  |
+6 |     my_var: int = 140 $ 2;
+  |                       ^
+  = help: remove this character
+
+"#,
+        "Compiler did not report the expected diagnostic"
+    );
+}
+
+#[test]
+fn test_errors_3_color() {
+    let stderr = compile_and_fail(Path::new("ex/errors/3.ob"), &["--color=always"]);
+    assert!(
+        stderr.starts_with("\x1b[1;31merror\x1b[0m: \x1b[1mUnknown character '$'.\x1b[0m\n"),
+        "Compiler did not color the diagnostic"
+    );
+}
+
+#[test]
+fn test_errors_4() {
+    let stderr = compile_and_fail(Path::new("ex/errors/4.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Expected an identifier, got Colon instead.
+ --> ex/errors/4.ob:6:8
+  |
+6 | helper(: int = {
+  |        ^
+
+error: Expected the start of an expression, got Semi instead.
+  --> ex/errors/4.ob:11:18
+   |
+11 |     first: int = ;
+   |                  ^
+
+error: Expected the symbol Colon, got Int instead.
+  --> ex/errors/4.ob:12:12
+   |
+12 |     second int = 2;
+   |            ^^^
+
+"#,
+        "Compiler did not report every syntax error"
+    );
+}
+
+#[test]
+fn test_errors_5() {
+    let stderr = compile_and_fail(Path::new("ex/errors/5.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Function add takes 2 argument(s), but 1 were given.
+  --> ex/errors/5.ob:10:12
+   |
+10 |     return add(1);
+   |            ^^^^^^
+
+"#,
+        "Compiler did not report the expected diagnostic"
+    );
+}
+
+#[test]
+fn test_errors_6() {
+    let stderr = compile_and_fail(Path::new("ex/errors/6.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Cannot reassign the constant limit.
+ --> ex/errors/6.ob:8:5
+  |
+8 |     limit = 11;
+  |     ^^^^^
+  = note: limit is declared with `const`
+  = help: remove `const` from its declaration to make it mutable
+
+error: Variable count not found.
+  --> ex/errors/6.ob:13:5
+   |
+13 |     count = 1;
+   |     ^^^^^
+   = help: declare it before using it, e.g. `count: int = 0;`
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_6() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/6.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/6");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 161,
+        "Program did not exit with the expected code"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_6_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/6.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_6.s"),
+    );
+}
+
+#[test]
+fn test_7() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/7.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/7");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 101,
+        "Program did not exit with the expected code"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_7_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/7.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_7.s"),
+    );
+}
+
+#[test]
+fn test_errors_7() {
+    let stderr = compile_and_fail(Path::new("ex/errors/7.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: `break` outside of a loop.
+ --> ex/errors/7.ob:7:9
+  |
+7 |         break;
+  |         ^^^^^
+  = note: `break` can only be used inside a while loop
+
+error: `continue` outside of a loop.
+ --> ex/errors/7.ob:9:5
+  |
+9 |     continue;
+  |     ^^^^^^^^
+  = note: `continue` can only be used inside a while loop
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_8() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/8.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/8");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 179,
+        "Program did not exit with the expected code"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_8_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/8.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_8.s"),
+    );
+}
+
+#[test]
+fn test_errors_8() {
+    let stderr = compile_and_fail(Path::new("ex/errors/8.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Function sign does not return a value on every path.
+ --> ex/errors/8.ob:1:15
+  |
+1 | sign(x: int): int = {
+  |               ^^^
+  = note: the end of its body can be reached without a `return`
+  = help: add a `return` at the end of the body
+
+error: Function forever does not return a value on every path.
+ --> ex/errors/8.ob:9:12
+  |
+9 | forever(): int = {
+  |            ^^^
+  = note: the end of its body can be reached without a `return`
+  = help: add a `return` at the end of the body
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_errors_9() {
+    let stderr = compile_and_fail(Path::new("ex/errors/9.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Function add is already defined.
+ --> ex/errors/9.ob:6:1
+  |
+6 | add(x: int, y: int): int = {
+  | ^^^
+  = note: both functions take (int, int)
+  = help: give one of the functions a different name
+
+error: Variable a is already declared in this scope.
+ --> ex/errors/9.ob:2:5
+  |
+2 |     a: int = 1;
+  |     ^
+  = help: use `a = ...;` to reassign it, or give the new variable a different name
+
+error: Variable y is already declared in this scope.
+  --> ex/errors/9.ob:17:5
+   |
+17 |     y: int = 0;
+   |     ^
+   = help: use `y = ...;` to reassign it, or give the new variable a different name
+
+error: Function main not found.
+ --> ex/errors/9.ob:1:1
+  |
+1 | add(a: int, b: int): int = {
+  | ^
+  = note: every program starts by calling `main`
+  = help: add `main(): int = { return 0; }`
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_errors_10() {
+    let stderr = compile_and_fail(Path::new("ex/errors/10.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Mismatched types: expected bool, found int.
+ --> ex/errors/10.ob:2:12
+  |
+2 |     return n % 2;
+  |            ^^^^^
+  = note: the function returns bool
+
+error: Mismatched types: expected int, found bool.
+  --> ex/errors/10.ob:10:14
+   |
+10 |     x: int = true;
+   |              ^^^^
+   = note: x is declared as int
+
+error: Mismatched types: expected bool, found int.
+  --> ex/errors/10.ob:12:12
+   |
+12 |     flag = 3;
+   |            ^
+   = note: flag is declared as bool
+
+error: Mismatched types: expected bool, found int.
+  --> ex/errors/10.ob:13:9
+   |
+13 |     if (x) {
+   |         ^
+   = note: the condition of an if statement must be of type bool
+
+error: Mismatched types: expected int, found bool.
+  --> ex/errors/10.ob:14:20
+   |
+14 |         return add(flag, 2) == 1;
+   |                    ^^^^
+   = note: parameter 1 of add is of type int
+
+error: Mismatched types: expected bool, found int.
+  --> ex/errors/10.ob:16:13
+   |
+16 |     while (!x) {
+   |             ^
+   = note: `!` can only be applied to bool
+
+error: Mismatched types: expected int or float, found bool.
+  --> ex/errors/10.ob:17:14
+   |
+17 |         x = -flag;
+   |              ^^^^
+   = note: `-` can only be applied to int or float
+
+error: Mismatched types: expected bool, found int.
+  --> ex/errors/10.ob:19:20
+   |
+19 |     return flag == 2 || x < 1;
+   |                    ^
+   = note: both sides of `==` must have the same type
+
+error: Function main must return an int.
+ --> ex/errors/10.ob:9:9
+  |
+9 | main(): bool = {
+  |         ^^^^
+  = note: the value returned by main is the exit code of the program
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_9() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/9.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/9");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 239,
+        "Program did not exit with the expected code"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_9_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/9.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_9.s"),
+    );
+}
+
+#[test]
+fn test_errors_11() {
+    let stderr = compile_and_fail(Path::new("ex/errors/11.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Empty character literal.
+ --> ex/errors/11.ob:6:19
+  |
+6 |     empty: char = '';
+  |                   ^^
+  = help: put a character between the quotes, e.g. `'a'`
+
+error: Unknown escape sequence `\q`.
+ --> ex/errors/11.ob:7:21
+  |
+7 |     escape: char = '\q';
+  |                     ^^
+  = help: the supported escapes are `\n`, `\t`, `\r`, `\0`, `\\`, `\'` and `\"`
+
+error: Character literals can only contain one character.
+ --> ex/errors/11.ob:8:18
+  |
+8 |     long: char = 'ab';
+  |                  ^^^^
+
+error: Character '€' does not fit in 8 bits.
+ --> ex/errors/11.ob:9:18
+  |
+9 |     wide: char = '€';
+  |                  ^^^
+
+error: Unknown escape sequence `\x`.
+  --> ex/errors/11.ob:10:24
+   |
+10 |     bad: string = "fine\x but \y";
+   |                        ^^
+   = help: the supported escapes are `\n`, `\t`, `\r`, `\0`, `\\`, `\'` and `\"`
+
+error: Unterminated string literal.
+  --> ex/errors/11.ob:11:20
+   |
+11 |     open: string = "never closed;
+   |                    ^
+   = help: close the string literal with `"`
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_errors_12() {
+    let stderr = compile_and_fail(Path::new("ex/errors/12.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Mismatched types: expected float, found int.
+ --> ex/errors/12.ob:6:22
+  |
+6 |     return (a + b) / 2;
+  |                      ^
+  = note: both sides of `/` must have the same type
+
+error: Mismatched types: expected int, found float.
+  --> ex/errors/12.ob:10:16
+   |
+10 |     x: float = 1.5 % 2.0;
+   |                ^^^
+   = note: `%` can only be applied to int
+
+error: Mismatched types: expected int, float or string, found char.
+  --> ex/errors/12.ob:11:15
+   |
+11 |     c: char = 'a' + 'b';
+   |               ^^^
+   = note: `+` can only be applied to int, float or string
+
+error: Mismatched types: expected float, found int.
+  --> ex/errors/12.ob:12:27
+   |
+12 |     y: int = average(1.0, 3);
+   |                           ^
+   = note: parameter 2 of average is of type float
+
+error: Mismatched types: expected int, found float.
+  --> ex/errors/12.ob:12:14
+   |
+12 |     y: int = average(1.0, 3);
+   |              ^^^^^^^^^^^^^^^
+   = note: y is declared as int
+
+error: Mismatched types: expected char, found int.
+  --> ex/errors/12.ob:13:13
+   |
+13 |     if (c < 1 || x > 1.0 || true < false) {
+   |             ^
+   = note: both sides of `<` must have the same type
+
+error: Mismatched types: expected int, float or char, found bool.
+  --> ex/errors/12.ob:13:29
+   |
+13 |     if (c < 1 || x > 1.0 || true < false) {
+   |                             ^^^^
+   = note: `<` can only be applied to int, float or char
+
+error: Mismatched types: expected int or float, found char.
+  --> ex/errors/12.ob:14:17
+   |
+14 |         return -'a';
+   |                 ^^^
+   = note: `-` can only be applied to int or float
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_10() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/10.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/10");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 31, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_10_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/10.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_10.s"),
+    );
+}
+
+#[test]
+fn test_errors_13() {
+    let stderr = compile_and_fail(Path::new("ex/errors/13.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Function len is already defined.
+ --> ex/errors/13.ob:6:1
+  |
+6 | len(s: string): int = {
+  | ^^^
+  = note: `len` is a builtin function
+  = help: give one of the functions a different name
+
+error: Mismatched types: expected string, found char.
+  --> ex/errors/13.ob:12:28
+   |
+12 |     shout: string = name + '!';
+   |                            ^^^
+   = note: both sides of `+` must have the same type
+
+error: Mismatched types: expected int, float or char, found string.
+  --> ex/errors/13.ob:13:9
+   |
+13 |     if (name < "z" || name == 'o') {
+   |         ^^^^
+   = note: `<` can only be applied to int, float or char
+
+error: Mismatched types: expected string, found char.
+  --> ex/errors/13.ob:13:31
+   |
+13 |     if (name < "z" || name == 'o') {
+   |                               ^^^
+   = note: both sides of `==` must have the same type
+
+error: Mismatched types: expected string or a list, found int.
+  --> ex/errors/13.ob:14:20
+   |
+14 |         return len(42);
+   |                    ^^
+   = note: parameter 1 of len is a string or a list
+
+error: Mismatched types: expected int or float, found string.
+  --> ex/errors/13.ob:16:13
+   |
+16 |     return -name;
+   |             ^^^^
+   = note: `-` can only be applied to int or float
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_11() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/11.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/11");
+
+    let (exit_code, stdout, _) = compile_and_run_with_output(prog_path, exe_path);
+    assert_eq!(exit_code, 0, "Program did not exit with the expected code");
+    assert_eq!(
+        stdout,
+        "Hello, World!
+0
+-42
+9223372036854775807
+-9223372036854775808
+3.140000
+-0.500000
+1.000000
+100000000000000000000.000000
+nan
+-inf
+true false
+3
+3 2 1 liftoff!
+",
+        "Program did not print the expected output"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_11_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/11.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_11.s"),
+    );
+}
+
+#[test]
+fn test_errors_14() {
+    let stderr = compile_and_fail(Path::new("ex/errors/14.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Function print cannot take (int, int).
+ --> ex/errors/14.ob:7:5
+  |
+7 |     print(1, 2);
+  |     ^^^^^^^^^^^
+  = note: print can take (int), (float), (bool), (char) or (string)
+
+error: Variable print not found.
+ --> ex/errors/14.ob:8:11
+  |
+8 |     print(print);
+  |           ^^^^^
+  = help: declare it before using it, e.g. `print: int = 0;`
+
+error: Mismatched types: expected int, found void.
+ --> ex/errors/14.ob:9:14
+  |
+9 |     x: int = print(1);
+  |              ^^^^^^^^
+  = note: x is declared as int
+
+error: Mismatched types: expected a value, found void.
+  --> ex/errors/14.ob:10:9
+   |
+10 |     if (print("a") == print("b")) {
+   |         ^^^^^^^^^^
+   = note: `==` can only be applied to values
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_12() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/12.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/12");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 63, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_12_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/12.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_12.s"),
+    );
+}
+
+#[test]
+fn test_13() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/13.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/13");
+
+    let (exit_code, stdout, stderr) = compile_and_run_with_output(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 101,
+        "Program did not exit with the expected code"
+    );
+    assert_eq!(
+        stdout, "0\n1\n2\n",
+        "Program did not print the expected output"
+    );
+    assert_eq!(
+        stderr, "error: Index 3 is out of bounds for length 3.\n",
+        "Program did not report the expected error"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_errors_15() {
+    let stderr = compile_and_fail(Path::new("ex/errors/15.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Mismatched types: expected int, found float.
+  --> ex/errors/15.ob:11:27
+   |
+11 |     mixed: [int; 3] = [1, 2.0, 3];
+   |                           ^^^
+   = note: all the elements of an array must have the same type
+
+error: Mismatched types: expected [int; 3], found [int; 4].
+  --> ex/errors/15.ob:12:23
+   |
+12 |     short: [int; 3] = primes;
+   |                       ^^^^^^
+   = note: short is declared as [int; 3]
+
+error: Cannot modify the constant primes.
+  --> ex/errors/15.ob:13:5
+   |
+13 |     primes[0] = 1;
+   |     ^^^^^^
+   = note: primes is declared with `const`
+   = help: remove `const` from its declaration to make it mutable
+
+error: Index 4 is out of bounds.
+  --> ex/errors/15.ob:14:21
+   |
+14 |     x: int = primes[4];
+   |                     ^
+   = note: the array is of type [int; 4], so the index must be between 0 and 3
+
+error: Mismatched types: expected int, found bool.
+  --> ex/errors/15.ob:15:21
+   |
+15 |     y: int = primes[true];
+   |                     ^^^^
+   = note: arrays are indexed by an int
+
+error: Mismatched types: expected an array or a list, found int.
+  --> ex/errors/15.ob:16:14
+   |
+16 |     z: int = x[0];
+   |              ^
+   = note: only arrays and lists can be indexed
+
+error: Mismatched types: expected int, found char.
+  --> ex/errors/15.ob:17:16
+   |
+17 |     short[1] = 'a';
+   |                ^^^
+   = note: the elements of the array are of type int
+
+error: Arrays cannot be compared with `==`.
+  --> ex/errors/15.ob:18:9
+   |
+18 |     if (primes == primes) {
+   |         ^^^^^^
+   = help: compare their elements one by one instead
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_14() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/14.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/14");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 63, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_14_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/14.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_14.s"),
+    );
+}
+
+#[test]
+fn test_15() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/15.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/15");
+
+    let (exit_code, stdout, stderr) = compile_and_run_with_output(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 101,
+        "Program did not exit with the expected code"
+    );
+    assert_eq!(
+        stdout, "in bounds\n2\n3\n",
+        "Program did not print the expected output"
+    );
+    assert_eq!(
+        stderr, "error: Cannot pop from an empty list.\n",
+        "Program did not report the expected error"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_errors_16() {
+    let stderr = compile_and_fail(Path::new("ex/errors/16.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Mismatched types: expected string, found char.
+  --> ex/errors/16.ob:11:17
+   |
+11 |     push(names, 'a');
+   |                 ^^^
+   = note: the elements of the list are of type string
+
+error: Cannot modify the constant primes.
+  --> ex/errors/16.ob:12:10
+   |
+12 |     push(primes, 2);
+   |          ^^^^^^
+   = note: primes is declared with `const`
+   = help: remove `const` from its declaration to make it mutable
+
+error: Mismatched types: expected int, found string.
+  --> ex/errors/16.ob:13:14
+   |
+13 |     x: int = pop(names);
+   |              ^^^^^^^^^^
+   = note: x is declared as int
+
+error: Mismatched types: expected string or a list, found int.
+  --> ex/errors/16.ob:14:18
+   |
+14 |     y: int = len(x);
+   |                  ^
+   = note: parameter 1 of len is a string or a list
+
+error: Mismatched types: expected a list, found int.
+  --> ex/errors/16.ob:15:10
+   |
+15 |     push(x, 1);
+   |          ^
+   = note: parameter 1 of push is a list
+
+error: Cannot infer the type of `[]`.
+  --> ex/errors/16.ob:16:22
+   |
+16 |     empty: int = len([]);
+   |                      ^^
+   = note: `[]` is an empty list of any type
+   = help: declare a variable with the type of the list first, e.g. `l: [int] = [];`
+
+error: Lists cannot be compared with `==`.
+  --> ex/errors/16.ob:17:9
+   |
+17 |     if (names == names) {
+   |         ^^^^^
+   = help: compare their elements one by one instead
+
+error: Function pop takes 1 argument(s), but 2 were given.
+  --> ex/errors/16.ob:20:28
+   |
+20 |     return x + y + empty + pop(primes, 1);
+   |                            ^^^^^^^^^^^^^^
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_16() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/16.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/16");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 63, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_16_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/16.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_16.s"),
+    );
+}
+
+#[test]
+fn test_errors_17() {
+    let stderr = compile_and_fail(Path::new("ex/errors/17.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Field x is already defined in Point.
+  --> ex/errors/17.ob:15:9
+   |
+15 |     pub x: float;
+   |         ^
+   = help: give one of the fields a different name
+
+error: Mismatched types: expected Wrong, found int.
+  --> ex/errors/17.ob:27:18
+   |
+27 |     pub Wrong(): int = {
+   |                  ^^^
+   = note: a constructor returns the object it initializes
+
+error: Function Point is already defined.
+  --> ex/errors/17.ob:31:1
+   |
+31 | Point(): int = {
+   | ^^^^^
+   = note: `Point` is a class
+   = help: give one of the functions a different name
+
+error: Class Point has no field z.
+  --> ex/errors/17.ob:18:14
+   |
+18 |         this.z = 0;
+   |              ^
+
+error: Constructors cannot return a value.
+  --> ex/errors/17.ob:19:9
+   |
+19 |         return this;
+   |         ^^^^^^^^^^^
+   = note: a constructor returns the object it initializes
+   = help: remove the `return`
+
+error: Field y of Point is not initialized by the constructor.
+  --> ex/errors/17.ob:16:9
+   |
+16 |     pub Point(x: int): Point = {
+   |         ^^^^^
+   = help: assign it in the body of the constructor, e.g. `this.y = ...;`
+
+error: Cannot modify the constant p.
+  --> ex/errors/17.ob:37:5
+   |
+37 |     p.x = 2;
+   |     ^
+   = note: p is declared with `const`
+   = help: remove `const` from its declaration to make it mutable
+
+error: Class Circle not found.
+  --> ex/errors/17.ob:38:8
+   |
+38 |     c: Circle = Point(3);
+   |        ^^^^^^
+   = help: classes are declared as `class Name { ... }`
+
+error: Class Empty has no constructor.
+  --> ex/errors/17.ob:39:16
+   |
+39 |     e: Empty = Empty();
+   |                ^^^^^
+   = help: add one to the class, e.g. `pub Empty(): Empty = { ... }`
+
+error: `this` outside of a class.
+  --> ex/errors/17.ob:40:14
+   |
+40 |     n: int = this.x;
+   |              ^^^^
+   = note: `this` can only be used inside a constructor or a method
+
+error: Objects cannot be compared with `==`.
+  --> ex/errors/17.ob:41:9
+   |
+41 |     if (p == p) {
+   |         ^
+   = help: compare their fields one by one instead
+
+error: Mismatched types: expected an object, found int.
+  --> ex/errors/17.ob:42:16
+   |
+42 |         return p.y.x;
+   |                ^^^
+   = note: only objects have fields
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_17() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/17.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/17");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 63, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_17_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/17.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_17.s"),
+    );
+}
+
+#[test]
+fn test_errors_18() {
+    let stderr = compile_and_fail(Path::new("ex/errors/18.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Method x is already defined in Point.
+  --> ex/errors/18.ob:22:9
+   |
+22 |     pub x(): int = {
+   |         ^
+   = note: `x` is a field
+   = help: give one of the members a different name
+
+error: Method moved does not return a value on every path.
+  --> ex/errors/18.ob:26:25
+   |
+26 |     pub moved(dx: int): Point = {
+   |                         ^^^^^
+   = note: the end of its body can be reached without a `return`
+   = help: add a `return` at the end of the body
+
+error: Field x of Point is private.
+  --> ex/errors/18.ob:42:16
+   |
+42 |     n: int = a.x + a.secret();
+   |                ^
+   = note: private members can only be used inside the class (Point)
+   = help: declare the member with `pub` to use it anywhere
+
+error: Method secret of Point is private.
+  --> ex/errors/18.ob:42:22
+   |
+42 |     n: int = a.x + a.secret();
+   |                      ^^^^^^
+   = note: private members can only be used inside the class (Point)
+   = help: declare the member with `pub` to use it anywhere
+
+error: Constructor Token of Token is private.
+  --> ex/errors/18.ob:43:16
+   |
+43 |     t: Token = Token();
+   |                ^^^^^
+   = note: private members can only be used inside the class (Token)
+   = help: declare the member with `pub` to use it anywhere
+
+error: Class Point has no method missing.
+  --> ex/errors/18.ob:44:13
+   |
+44 |     a.y = a.missing();
+   |             ^^^^^^^
+
+error: Mismatched types: expected an object, found int.
+  --> ex/errors/18.ob:45:14
+   |
+45 |     m: int = n.secret();
+   |              ^
+   = note: only objects have methods
+
+error: Method moved takes 1 argument(s), but 0 were given.
+  --> ex/errors/18.ob:46:12
+   |
+46 |     return a.moved() + a.y.moved(1);
+   |            ^^^^^^^^^
+
+error: Mismatched types: expected int, float or string, found Point.
+  --> ex/errors/18.ob:46:12
+   |
+46 |     return a.moved() + a.y.moved(1);
+   |            ^^^^^^^^^
+   = note: `+` can only be applied to int, float or string
+
+error: Mismatched types: expected an object, found int.
+  --> ex/errors/18.ob:46:24
+   |
+46 |     return a.moved() + a.y.moved(1);
+   |                        ^^^
+   = note: only objects have methods
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_18() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/18.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/18");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 63, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_18_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/18.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_18.s"),
+    );
+}
+
+#[test]
+fn test_errors_19() {
+    let stderr = compile_and_fail(Path::new("ex/errors/19.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Method scale is already defined in Point.
+  --> ex/errors/19.ob:27:9
+   |
+27 |     pub scale(factor: int): int = {
+   |         ^^^^^
+   = note: both methods take (int)
+   = help: give one of the members a different name
+
+error: Constructor Point is already defined.
+  --> ex/errors/19.ob:15:9
+   |
+15 |     pub Point(value: int): Point = {
+   |         ^^^^^
+   = note: both constructors take (int)
+   = help: give the constructors different parameters
+
+error: Function first is already defined.
+  --> ex/errors/19.ob:44:1
+   |
+44 | first(l: [int]): int = {
+   | ^^^^^
+   = note: both functions take ([int])
+   = help: give one of the functions a different name
+
+error: Function main is already defined.
+  --> ex/errors/19.ob:52:1
+   |
+52 | main(): int = {
+   | ^^^^
+   = note: `main` cannot be overloaded
+   = help: give one of the functions a different name
+
+error: Constructor Point cannot take (bool).
+  --> ex/errors/19.ob:53:16
+   |
+53 |     p: Point = Point(true);
+   |                ^^^^^^^^^^^
+   = note: Point can take (int) or ()
+
+error: Method scale cannot take (char).
+  --> ex/errors/19.ob:54:14
+   |
+54 |     n: int = p.scale('c') + first([]) + first([1], 2);
+   |              ^^^^^^^^^^^^
+   = note: scale can take (int) or (float)
+
+error: Function first can take ([]) in more than one way.
+  --> ex/errors/19.ob:54:29
+   |
+54 |     n: int = p.scale('c') + first([]) + first([1], 2);
+   |                             ^^^^^^^^^
+   = note: first can take ([int]) or ([bool])
+   = help: give `[]` a type by declaring a variable for it first, e.g. `l: [int] = [];`
+
+error: Function first cannot take ([int; 1], int).
+  --> ex/errors/19.ob:54:41
+   |
+54 |     n: int = p.scale('c') + first([]) + first([1], 2);
+   |                                         ^^^^^^^^^^^^^
+   = note: first can take ([int]) or ([bool])
+
+error: Function main cannot take parameters.
+  --> ex/errors/19.ob:48:6
+   |
+48 | main(x: int): int = {
+   |      ^^^^^^
+   = help: remove the parameters of `main`
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_19() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/19.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/19");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 63, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_19_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/19.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_19.s"),
+    );
+}
+
+#[test]
+fn test_errors_20() {
+    let stderr = compile_and_fail(Path::new("ex/errors/20.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Class Tiger not found.
+  --> ex/errors/20.ob:51:12
+   |
+51 | class Cat: Tiger {}
+   |            ^^^^^
+   = help: classes are declared as `class Name { ... }`
+
+error: Class Circle extends itself.
+  --> ex/errors/20.ob:55:15
+   |
+55 | class Circle: Loop {}
+   |               ^^^^
+   = note: Loop is Circle or extends it, through other classes
+
+error: Mismatched types: expected int, found bool.
+  --> ex/errors/20.ob:28:18
+   |
+28 |     pub speak(): bool = {
+   |                  ^^^^
+   = note: speak overrides the method of Animal, so it must return the same type
+
+error: Field speak is already defined in Puppy.
+  --> ex/errors/20.ob:40:9
+   |
+40 |     pub speak: int;
+   |         ^^^^^
+   = note: `speak` is a method of Animal
+   = help: give one of the members a different name
+
+error: Method speak of Puppy cannot change the visibility of the method it overrides.
+  --> ex/errors/20.ob:46:9
+   |
+46 |     prv speak(): int = {
+   |         ^^^^^
+   = note: `speak` is declared with `pub` in Animal
+   = help: declare the method with `pub`
+
+error: Field legs of Animal is private.
+  --> ex/errors/20.ob:25:14
+   |
+25 |         this.legs = 4;
+   |              ^^^^
+   = note: private members can only be used inside the class (Animal)
+   = help: declare the member with `pub` to use it anywhere
+
+error: `super(...)` outside of a constructor.
+  --> ex/errors/20.ob:33:9
+   |
+33 |         super(4);
+   |         ^^^^^^^^
+   = note: a method calls the methods of the base class with `super.method(...)`
+
+error: `super` can only be used to call the base class.
+  --> ex/errors/20.ob:34:16
+   |
+34 |         return super;
+   |                ^^^^^
+   = note: `super` is used as `super.method(...)` or `super(...)`
+   = help: use `this` to refer to the object itself
+
+error: Field legs of Puppy is not initialized by the constructor.
+  --> ex/errors/20.ob:42:9
+   |
+42 |     pub Puppy(): Puppy = {
+   |         ^^^^^
+   = help: call a constructor of Animal in the body of the constructor, e.g. `super(...);`
+
+error: Class Rock has no base class.
+  --> ex/errors/20.ob:59:9
+   |
+59 |         super.speak();
+   |         ^^^^^
+   = note: `super` refers to the class that Rock extends, as in `class Rock: Base { ... }`
+
+error: `super` outside of a class.
+  --> ex/errors/20.ob:65:12
+   |
+65 |     return super.speak();
+   |            ^^^^^
+   = note: `super` can only be used inside a constructor or a method
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_20() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/20.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/20");
+
+    let exit_code = compile_and_run(prog_path, exe_path);
+    assert_eq!(exit_code, 63, "Program did not exit with the expected code");
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_20_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/20.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_20.s"),
+    );
+}
+
+#[test]
+fn test_errors_21() {
+    let stderr = compile_and_fail(Path::new("ex/errors/21.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Enum Shape is already defined.
+  --> ex/errors/21.ob:11:6
+   |
+11 | enum Shape { CIRCLE }
+   |      ^^^^^
+   = note: `Shape` is a class
+   = help: give one of the types a different name
+
+error: Variant RED is already defined in Color.
+  --> ex/errors/21.ob:13:26
+   |
+13 | enum Color { RED, GREEN, RED }
+   |                          ^^^
+   = help: give one of the variants a different name
+
+error: Enum Color has no variant PURPLE.
+  --> ex/errors/21.ob:18:22
+   |
+18 |     c: Color = Color.PURPLE;
+   |                      ^^^^^^
+   = note: Color is RED or GREEN
+
+error: Mismatched types: expected int, found Color.
+  --> ex/errors/21.ob:19:14
+   |
+19 |     n: int = Color.GREEN;
+   |              ^^^^^^^^^^^
+   = note: n is declared as int
+
+error: Mismatched types: expected Direction, found Color.
+  --> ex/errors/21.ob:20:20
+   |
+20 |     d: Direction = Color.RED;
+   |                    ^^^^^^^^^
+   = note: d is declared as Direction
+
+error: Mismatched types: expected Color, found Direction.
+  --> ex/errors/21.ob:21:22
+   |
+21 |     if (Color.RED == Direction.UP || Color.RED < Color.GREEN) {
+   |                      ^^^^^^^^^^^^
+   = note: both sides of `==` must have the same type
+
+error: Mismatched types: expected int, float or char, found Color.
+  --> ex/errors/21.ob:21:38
+   |
+21 |     if (Color.RED == Direction.UP || Color.RED < Color.GREEN) {
+   |                                      ^^^^^^^^^
+   = note: `<` can only be applied to int, float or char
+
+error: Mismatched types: expected int, float or string, found Color.
+  --> ex/errors/21.ob:22:16
+   |
+22 |         return Color.RED + 1;
+   |                ^^^^^^^^^
+   = note: `+` can only be applied to int, float or string
+
+error: Cannot assign to Color.RED.
+  --> ex/errors/21.ob:24:5
+   |
+24 |     Color.RED = Color.GREEN;
+   |     ^^^^^^^^^
+   = note: the variants of an enum are constants
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_errors_22() {
+    let stderr = compile_and_fail(Path::new("ex/errors/22.ob"), &[]);
+    assert_eq!(
+        stderr,
+        r#"error: Integer literal -9223372036854775809 is too small.
+ --> ex/errors/22.ob:8:22
+  |
+8 |     too_small: int = -9223372036854775809;
+  |                      ^^^^^^^^^^^^^^^^^^^^
+
+error: Integer literal 9223372036854775808 is too large.
+ --> ex/errors/22.ob:9:22
+  |
+9 |     too_large: int = 9223372036854775808;
+  |                      ^^^^^^^^^^^^^^^^^^^
+
+"#,
+        "Compiler did not report the expected diagnostics"
+    );
+}
+
+#[test]
+fn test_21() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/21.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/21");
+
+    let (exit_code, stdout, stderr) = compile_and_run_with_output(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 101,
+        "Program did not exit with the expected code"
+    );
+    assert_eq!(
+        stdout, "1\n6\n",
+        "Program did not print the expected output"
+    );
+    assert_eq!(
+        stderr, "error: Index 0 is out of bounds for length 0.\n",
+        "Program did not report the expected error"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_21_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/21.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_21.s"),
+    );
+}
+
+#[test]
+fn test_22() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/22.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/22");
+
+    let (exit_code, stdout, stderr) = compile_and_run_with_output(prog_path, exe_path);
+    assert_eq!(
+        exit_code, 102,
+        "Program did not exit with the expected code"
+    );
+    assert_eq!(
+        stdout, "true true 0 -3 -1 1\n",
+        "Program did not print the expected output"
+    );
+    assert_eq!(
+        stderr, "error: Division by zero.\n",
+        "Program did not report the expected error"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_22_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/22.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_22.s"),
+    );
+}
+
+#[test]
+fn test_23() {
+    // Path of the file to compile
+    let prog_path = Path::new("ex/23.ob");
+    // Path of the executable that will be created
+    let exe_path = Path::new("ex/23");
+
+    let compiler_bin = Path::new("target/debug/objection");
+    let process = Command::new(compiler_bin)
+        .arg(prog_path)
+        .status()
+        .expect("Failed to run compiler");
+    assert!(process.success(), "Failed to compile");
+
+    // Run the compiled program with a limit on its memory, so that the heap cannot grow forever
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg("ulimit -v 65536 && exec ./ex/23")
+        .output()
+        .expect("Failed to run compiled program");
+    assert_eq!(
+        output.status.code(),
+        Some(103),
+        "Program did not exit with the expected code"
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).expect("Output is not valid UTF-8"),
+        "error: Out of memory.\n",
+        "Program did not report the expected error"
+    );
+
+    // Clean up
+    let _ = fs::remove_file(exe_path);
+}
+
+#[test]
+fn test_23_linux_aarch64_snapshot() {
+    assert_snapshot(
+        Path::new("ex/23.ob"),
+        "linux",
+        "aarch64",
+        Path::new("tests/snapshots/linux_aarch64_23.s"),
+    );
+}
@@ -0,0 +1,1154 @@
+.global _start
+_start:
+    bl objection_main.v
+    mov x8, #93
+    svc #0
+.global objection_Point.Point.ii
+objection_Point.Point.ii:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str x0, [x29, #-8]
+    str x1, [x29, #-16]
+    str x2, [x29, #-24]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-16]
+    ldr x10, [x29, #-32]
+    add x10, x10, #8
+    str x9, [x10]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-24]
+    ldr x10, [x29, #-32]
+    add x10, x10, #16
+    str x9, [x10]
+    ldr x0, [x29, #-8]
+.Lepilogue_1:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_Point.Point.i
+objection_Point.Point.i:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str x0, [x29, #-8]
+    str x1, [x29, #-16]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-24]
+    ldr x9, [x29, #-16]
+    ldr x10, [x29, #-24]
+    add x10, x10, #8
+    str x9, [x10]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-24]
+    ldr x9, [x29, #-16]
+    ldr x10, [x29, #-24]
+    add x10, x10, #16
+    str x9, [x10]
+    ldr x0, [x29, #-8]
+.Lepilogue_2:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_Point.Point.C5Point
+objection_Point.Point.C5Point:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str x0, [x29, #-8]
+    str x1, [x29, #-16]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-24]
+    ldr x9, [x29, #-16]
+    add x9, x9, #8
+    ldr x9, [x9]
+    ldr x10, [x29, #-24]
+    add x10, x10, #8
+    str x9, [x10]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-24]
+    ldr x9, [x29, #-16]
+    add x9, x9, #16
+    ldr x9, [x9]
+    ldr x10, [x29, #-24]
+    add x10, x10, #16
+    str x9, [x10]
+    ldr x0, [x29, #-8]
+.Lepilogue_3:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_Point.move.i
+objection_Point.move.i:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #48
+    str x0, [x29, #-8]
+    str x1, [x29, #-16]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-24]
+    ldr x9, [x29, #-16]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-16]
+    str x9, [x29, #-40]
+    ldr x0, [x29, #-24]
+    ldr x1, [x29, #-32]
+    ldr x2, [x29, #-40]
+    ldr x16, [x0]
+    ldr x16, [x16, #8]
+    blr x16
+    mov x9, x0
+    mov x0, x9
+    b .Lepilogue_4
+.Lepilogue_4:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_Point.move.ii
+objection_Point.move.ii:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #48
+    str x0, [x29, #-8]
+    str x1, [x29, #-16]
+    str x2, [x29, #-24]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-8]
+    add x9, x9, #8
+    ldr x9, [x9]
+    str x9, [x29, #-40]
+    ldr x9, [x29, #-16]
+    mov x10, x9
+    ldr x9, [x29, #-40]
+    add x9, x9, x10
+    ldr x10, [x29, #-32]
+    add x10, x10, #8
+    str x9, [x10]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-8]
+    add x9, x9, #16
+    ldr x9, [x9]
+    str x9, [x29, #-40]
+    ldr x9, [x29, #-24]
+    mov x10, x9
+    ldr x9, [x29, #-40]
+    add x9, x9, x10
+    ldr x10, [x29, #-32]
+    add x10, x10, #16
+    str x9, [x10]
+    ldr x9, [x29, #-8]
+    add x9, x9, #8
+    ldr x9, [x9]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-8]
+    add x9, x9, #16
+    ldr x9, [x9]
+    mov x10, x9
+    ldr x9, [x29, #-32]
+    add x9, x9, x10
+    mov x0, x9
+    b .Lepilogue_5
+.Lepilogue_5:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_Point.sum.v
+objection_Point.sum.v:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #16
+    str x0, [x29, #-8]
+    ldr x9, [x29, #-8]
+    add x9, x9, #8
+    ldr x9, [x9]
+    str x9, [x29, #-16]
+    ldr x9, [x29, #-8]
+    add x9, x9, #16
+    ldr x9, [x9]
+    mov x10, x9
+    ldr x9, [x29, #-16]
+    add x9, x9, x10
+    mov x0, x9
+    b .Lepilogue_6
+.Lepilogue_6:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_add.ii
+objection_add.ii:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str x0, [x29, #-8]
+    str x1, [x29, #-16]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-24]
+    ldr x9, [x29, #-16]
+    mov x10, x9
+    ldr x9, [x29, #-24]
+    add x9, x9, x10
+    mov x0, x9
+    b .Lepilogue_7
+.Lepilogue_7:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_add.ff
+objection_add.ff:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str d0, [x29, #-8]
+    str d1, [x29, #-16]
+    ldr d0, [x29, #-8]
+    str d0, [x29, #-24]
+    ldr d0, [x29, #-16]
+    fmov d1, d0
+    ldr d0, [x29, #-24]
+    fadd d0, d0, d1
+    b .Lepilogue_8
+.Lepilogue_8:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_add.iii
+objection_add.iii:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str x0, [x29, #-8]
+    str x1, [x29, #-16]
+    str x2, [x29, #-24]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-16]
+    mov x10, x9
+    ldr x9, [x29, #-32]
+    add x9, x9, x10
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-24]
+    mov x10, x9
+    ldr x9, [x29, #-32]
+    add x9, x9, x10
+    mov x0, x9
+    b .Lepilogue_9
+.Lepilogue_9:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_total.A3i
+objection_total.A3i:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #48
+    str x0, [x29, #-8]
+    ldr x9, [x29, #-8]
+    sub x10, x29, #32
+    mov x11, #24
+.Lcopy_11:
+    ldr x13, [x9], #8
+    str x13, [x10], #8
+    subs x11, x11, #8
+    b.ne .Lcopy_11
+    sub x9, x29, #32
+    str x9, [x29, #-40]
+    mov x9, #0
+    mov x11, #3
+    cmp x9, x11
+    b.lo .Lindex_ok_12
+    mov x0, x9
+    mov x1, x11
+    bl __objection_out_of_bounds
+.Lindex_ok_12:
+    ldr x10, [x29, #-40]
+    mov x11, #8
+    madd x9, x9, x11, x10
+    ldr x9, [x9]
+    str x9, [x29, #-40]
+    sub x9, x29, #32
+    str x9, [x29, #-48]
+    mov x9, #1
+    mov x11, #3
+    cmp x9, x11
+    b.lo .Lindex_ok_13
+    mov x0, x9
+    mov x1, x11
+    bl __objection_out_of_bounds
+.Lindex_ok_13:
+    ldr x10, [x29, #-48]
+    mov x11, #8
+    madd x9, x9, x11, x10
+    ldr x9, [x9]
+    mov x10, x9
+    ldr x9, [x29, #-40]
+    add x9, x9, x10
+    str x9, [x29, #-40]
+    sub x9, x29, #32
+    str x9, [x29, #-48]
+    mov x9, #2
+    mov x11, #3
+    cmp x9, x11
+    b.lo .Lindex_ok_14
+    mov x0, x9
+    mov x1, x11
+    bl __objection_out_of_bounds
+.Lindex_ok_14:
+    ldr x10, [x29, #-48]
+    mov x11, #8
+    madd x9, x9, x11, x10
+    ldr x9, [x9]
+    mov x10, x9
+    ldr x9, [x29, #-40]
+    add x9, x9, x10
+    mov x0, x9
+    b .Lepilogue_10
+.Lepilogue_10:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_total.Li
+objection_total.Li:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #48
+    str x0, [x29, #-8]
+    mov x9, #0
+    str x9, [x29, #-16]
+    mov x9, #0
+    str x9, [x29, #-24]
+.Lwhile_head_16:
+    ldr x9, [x29, #-24]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-8]
+    ldr x9, [x9]
+    mov x10, x9
+    ldr x9, [x29, #-32]
+    cmp x9, x10
+    cset x9, lt
+    cbz x9, .Lwhile_exit_17
+    ldr x9, [x29, #-16]
+    str x9, [x29, #-32]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-40]
+    ldr x9, [x29, #-24]
+    ldr x11, [x29, #-40]
+    ldr x11, [x11]
+    cmp x9, x11
+    b.lo .Lindex_ok_18
+    mov x0, x9
+    mov x1, x11
+    bl __objection_out_of_bounds
+.Lindex_ok_18:
+    ldr x10, [x29, #-40]
+    ldr x10, [x10, #16]
+    mov x11, #8
+    madd x9, x9, x11, x10
+    ldr x9, [x9]
+    mov x10, x9
+    ldr x9, [x29, #-32]
+    add x9, x9, x10
+    str x9, [x29, #-16]
+    ldr x9, [x29, #-24]
+    str x9, [x29, #-32]
+    mov x9, #1
+    mov x10, x9
+    ldr x9, [x29, #-32]
+    add x9, x9, x10
+    str x9, [x29, #-24]
+    b .Lwhile_head_16
+.Lwhile_exit_17:
+    ldr x9, [x29, #-16]
+    mov x0, x9
+    b .Lepilogue_15
+.Lepilogue_15:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_total.C5Point
+objection_total.C5Point:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #16
+    str x0, [x29, #-8]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-16]
+    ldr x0, [x29, #-16]
+    ldr x16, [x0]
+    ldr x16, [x16, #16]
+    blr x16
+    mov x9, x0
+    mov x0, x9
+    b .Lepilogue_19
+.Lepilogue_19:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_describe.c
+objection_describe.c:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #16
+    str x0, [x29, #-8]
+    ldrb w9, [x29, #-8]
+    str x9, [x29, #-16]
+    mov x9, #120
+    mov x10, x9
+    ldr x9, [x29, #-16]
+    cmp x9, x10
+    cset x9, eq
+    mov x0, x9
+    b .Lepilogue_20
+.Lepilogue_20:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_describe.s
+objection_describe.s:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #16
+    str x0, [x29, #-8]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-16]
+    ldr x0, [x29, #-16]
+    bl __objection_len
+    mov x9, x0
+    str x9, [x29, #-16]
+    mov x9, #2
+    mov x10, x9
+    ldr x9, [x29, #-16]
+    cmp x9, x10
+    cset x9, gt
+    mov x0, x9
+    b .Lepilogue_21
+.Lepilogue_21:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.global objection_main.v
+objection_main.v:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #64
+    mov x9, #1
+    str x9, [x29, #-8]
+    mov x9, #2
+    str x9, [x29, #-16]
+    ldr x0, [x29, #-8]
+    ldr x1, [x29, #-16]
+    bl objection_add.ii
+    mov x9, x0
+    str x9, [x29, #-8]
+    mov x9, #3
+    str x9, [x29, #-16]
+    mov x9, #4
+    str x9, [x29, #-24]
+    ldr x0, [x29, #-16]
+    ldr x1, [x29, #-24]
+    bl objection_add.ii
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-8]
+    add x9, x9, x10
+    str x9, [x29, #-8]
+    mov x9, #2
+    str x9, [x29, #-16]
+    mov x9, #2
+    str x9, [x29, #-24]
+    ldr x0, [x29, #-16]
+    ldr x1, [x29, #-24]
+    bl objection_add.ii
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-8]
+    sub x9, x9, x10
+    str x9, [x29, #-8]
+    mov x9, #0
+    str x9, [x29, #-16]
+    mov x9, #0
+    str x9, [x29, #-24]
+    mov x9, #0
+    str x9, [x29, #-32]
+    ldr x0, [x29, #-16]
+    ldr x1, [x29, #-24]
+    ldr x2, [x29, #-32]
+    bl objection_add.iii
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-8]
+    add x9, x9, x10
+    str x9, [x29, #-8]
+    ldr x9, =4609434218613702656
+    fmov d0, x9
+    str d0, [x29, #-16]
+    ldr x9, =4611686018427387904
+    fmov d0, x9
+    str d0, [x29, #-24]
+    ldr d0, [x29, #-16]
+    ldr d1, [x29, #-24]
+    bl objection_add.ff
+    str d0, [x29, #-16]
+    ldr x9, =4615063718147915776
+    fmov d0, x9
+    fmov d1, d0
+    ldr d0, [x29, #-16]
+    fcmp d0, d1
+    cset x9, ne
+    cbz x9, .Lif_else_23
+    mov x9, #1
+    mov x0, x9
+    b .Lepilogue_22
+    b .Lif_end_24
+.Lif_else_23:
+.Lif_end_24:
+    bl __objection_list_new
+    mov x9, x0
+    str x9, [x29, #-16]
+    ldr x9, [x29, #-16]
+    str x9, [x29, #-24]
+    mov x9, #1
+    str x9, [x29, #-32]
+    ldr x0, [x29, #-24]
+    mov x1, #8
+    bl __objection_list_push
+    mov x10, x0
+    ldr x9, [x29, #-32]
+    str x9, [x10]
+    ldr x9, [x29, #-16]
+    str x9, [x29, #-24]
+    mov x9, #2
+    str x9, [x29, #-32]
+    ldr x0, [x29, #-24]
+    mov x1, #8
+    bl __objection_list_push
+    mov x10, x0
+    ldr x9, [x29, #-32]
+    str x9, [x10]
+    ldr x9, [x29, #-16]
+    str x9, [x29, #-24]
+    mov x9, #3
+    str x9, [x29, #-32]
+    ldr x0, [x29, #-24]
+    mov x1, #8
+    bl __objection_list_push
+    mov x10, x0
+    ldr x9, [x29, #-32]
+    str x9, [x10]
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-24]
+    ldr x9, [x29, #-16]
+    str x9, [x29, #-32]
+    ldr x0, [x29, #-32]
+    bl objection_total.Li
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-24]
+    add x9, x9, x10
+    str x9, [x29, #-24]
+    bl __objection_list_new
+    mov x9, x0
+    str x9, [x29, #-32]
+    ldr x0, [x29, #-32]
+    bl objection_total.Li
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-24]
+    sub x9, x9, x10
+    str x9, [x29, #-24]
+    mov x9, #1
+    str x9, [x29, #-48]
+    mov x9, #2
+    str x9, [x29, #-40]
+    mov x9, #3
+    str x9, [x29, #-32]
+    sub x9, x29, #48
+    str x9, [x29, #-56]
+    ldr x0, [x29, #-56]
+    bl objection_total.A3i
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-24]
+    add x9, x9, x10
+    str x9, [x29, #-24]
+    mov x9, #6
+    mov x10, x9
+    ldr x9, [x29, #-24]
+    sub x9, x9, x10
+    str x9, [x29, #-8]
+    mov x0, #24
+    bl __objection_alloc
+    adrp x9, objection_Point.vtable
+    add x9, x9, :lo12:objection_Point.vtable
+    str x9, [x0]
+    str x0, [x29, #-24]
+    mov x9, #10
+    str x9, [x29, #-32]
+    ldr x0, [x29, #-24]
+    ldr x1, [x29, #-32]
+    bl objection_Point.Point.i
+    mov x9, x0
+    str x9, [x29, #-24]
+    mov x0, #24
+    bl __objection_alloc
+    adrp x9, objection_Point.vtable
+    add x9, x9, :lo12:objection_Point.vtable
+    str x9, [x0]
+    str x0, [x29, #-32]
+    mov x9, #3
+    str x9, [x29, #-40]
+    mov x9, #4
+    str x9, [x29, #-48]
+    ldr x0, [x29, #-32]
+    ldr x1, [x29, #-40]
+    ldr x2, [x29, #-48]
+    bl objection_Point.Point.ii
+    mov x9, x0
+    str x9, [x29, #-32]
+    mov x0, #24
+    bl __objection_alloc
+    adrp x9, objection_Point.vtable
+    add x9, x9, :lo12:objection_Point.vtable
+    str x9, [x0]
+    str x0, [x29, #-40]
+    ldr x9, [x29, #-32]
+    str x9, [x29, #-48]
+    ldr x0, [x29, #-40]
+    ldr x1, [x29, #-48]
+    bl objection_Point.Point.C5Point
+    mov x9, x0
+    str x9, [x29, #-40]
+    ldr x9, [x29, #-24]
+    str x9, [x29, #-48]
+    ldr x9, =-5
+    str x9, [x29, #-56]
+    ldr x0, [x29, #-48]
+    ldr x1, [x29, #-56]
+    ldr x16, [x0]
+    ldr x16, [x16, #0]
+    blr x16
+    mov x9, x0
+    ldr x9, [x29, #-40]
+    str x9, [x29, #-48]
+    mov x9, #1
+    str x9, [x29, #-56]
+    mov x9, #1
+    str x9, [x29, #-64]
+    ldr x0, [x29, #-48]
+    ldr x1, [x29, #-56]
+    ldr x2, [x29, #-64]
+    ldr x16, [x0]
+    ldr x16, [x16, #8]
+    blr x16
+    mov x9, x0
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-48]
+    ldr x9, [x29, #-24]
+    str x9, [x29, #-56]
+    ldr x0, [x29, #-56]
+    bl objection_total.C5Point
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-48]
+    add x9, x9, x10
+    str x9, [x29, #-48]
+    ldr x9, [x29, #-32]
+    str x9, [x29, #-56]
+    ldr x0, [x29, #-56]
+    bl objection_total.C5Point
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-48]
+    add x9, x9, x10
+    str x9, [x29, #-48]
+    ldr x9, [x29, #-40]
+    str x9, [x29, #-56]
+    ldr x0, [x29, #-56]
+    bl objection_total.C5Point
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-48]
+    add x9, x9, x10
+    str x9, [x29, #-8]
+    mov x9, #120
+    str x9, [x29, #-48]
+    ldr x0, [x29, #-48]
+    bl objection_describe.c
+    mov x9, x0
+    cmp x9, #0
+    cset x9, eq
+    cmp x9, #0
+    cset x9, ne
+    cbnz x9, .Llogic_end_28
+    adrp x9, .Lstring_29
+    add x9, x9, :lo12:.Lstring_29
+    str x9, [x29, #-48]
+    ldr x0, [x29, #-48]
+    bl objection_describe.s
+    mov x9, x0
+    cmp x9, #0
+    cset x9, ne
+.Llogic_end_28:
+    cmp x9, #0
+    cset x9, ne
+    cbnz x9, .Llogic_end_27
+    adrp x9, .Lstring_30
+    add x9, x9, :lo12:.Lstring_30
+    str x9, [x29, #-48]
+    ldr x0, [x29, #-48]
+    bl objection_describe.s
+    mov x9, x0
+    cmp x9, #0
+    cset x9, eq
+    cmp x9, #0
+    cset x9, ne
+.Llogic_end_27:
+    cbz x9, .Lif_else_25
+    mov x9, #2
+    mov x0, x9
+    b .Lepilogue_22
+    b .Lif_end_26
+.Lif_else_25:
+.Lif_end_26:
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-48]
+    mov x9, #10
+    str x9, [x29, #-56]
+    mov x9, #10
+    str x9, [x29, #-64]
+    ldr x0, [x29, #-56]
+    ldr x1, [x29, #-64]
+    bl objection_add.ii
+    mov x9, x0
+    str x9, [x29, #-56]
+    mov x9, #5
+    str x9, [x29, #-64]
+    ldr x0, [x29, #-56]
+    ldr x1, [x29, #-64]
+    bl objection_add.ii
+    mov x9, x0
+    mov x10, x9
+    ldr x9, [x29, #-48]
+    add x9, x9, x10
+    mov x0, x9
+    b .Lepilogue_22
+.Lepilogue_22:
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.data
+.p2align 3
+objection_Point.vtable:
+    .quad objection_Point.move.i
+    .quad objection_Point.move.ii
+    .quad objection_Point.sum.v
+.text
+__objection_alloc:
+    add x0, x0, #7
+    and x0, x0, #-8
+    adrp x9, .Lheap
+    add x9, x9, :lo12:.Lheap
+    ldp x10, x11, [x9]
+    add x12, x10, x0
+    cmp x12, x11
+    b.hi .Lalloc_grow
+    str x12, [x9]
+    mov x0, x10
+    ret
+.Lalloc_grow:
+    mov x1, #1048576
+    cmp x0, x1
+    csel x1, x0, x1, hi
+    stp x0, x1, [sp, #-16]!
+    mov x0, #0
+    mov x2, #3
+    mov x3, #34
+    mov x4, #-1
+    mov x5, #0
+    mov x8, #222
+    svc #0
+    cmn x0, #4096
+    b.hs .Lalloc_failed
+    ldp x12, x13, [sp], #16
+    add x10, x0, x12
+    add x11, x0, x13
+    adrp x9, .Lheap
+    add x9, x9, :lo12:.Lheap
+    stp x10, x11, [x9]
+    ret
+.Lalloc_failed:
+    adrp x1, .Lruntime_out_of_memory
+    add x1, x1, :lo12:.Lruntime_out_of_memory
+    ldr x2, [x1], #8
+    mov x0, #2
+    mov x8, #64
+    svc #0
+    mov x0, #103
+    mov x8, #93
+    svc #0
+__objection_string_concat:
+    stp x29, x30, [sp, #-32]!
+    mov x29, sp
+    stp x0, x1, [x29, #16]
+    ldr x9, [x0]
+    ldr x10, [x1]
+    add x0, x9, x10
+    add x0, x0, #8
+    bl __objection_alloc
+    ldp x1, x2, [x29, #16]
+    ldr x9, [x1], #8
+    ldr x10, [x2], #8
+    add x11, x9, x10
+    str x11, [x0]
+    add x12, x0, #8
+.Lconcat_lhs:
+    cbz x9, .Lconcat_rhs
+    ldrb w13, [x1], #1
+    strb w13, [x12], #1
+    sub x9, x9, #1
+    b .Lconcat_lhs
+.Lconcat_rhs:
+    cbz x10, .Lconcat_end
+    ldrb w13, [x2], #1
+    strb w13, [x12], #1
+    sub x10, x10, #1
+    b .Lconcat_rhs
+.Lconcat_end:
+    ldp x29, x30, [sp], #32
+    ret
+__objection_string_eq:
+    ldr x9, [x0], #8
+    ldr x10, [x1], #8
+    cmp x9, x10
+    b.ne .Lstring_eq_false
+.Lstring_eq_loop:
+    cbz x9, .Lstring_eq_true
+    ldrb w10, [x0], #1
+    ldrb w11, [x1], #1
+    cmp w10, w11
+    b.ne .Lstring_eq_false
+    sub x9, x9, #1
+    b .Lstring_eq_loop
+.Lstring_eq_true:
+    mov x0, #1
+    ret
+.Lstring_eq_false:
+    mov x0, #0
+    ret
+__objection_len:
+    ldr x0, [x0]
+    ret
+__objection_print_string:
+    ldr x2, [x0]
+    add x1, x0, #8
+    mov x0, #1
+    mov x8, #64
+    svc #0
+    ret
+__objection_print_char:
+    strb w0, [sp, #-16]!
+    mov x1, sp
+    mov x2, #1
+    mov x0, #1
+    mov x8, #64
+    svc #0
+    add sp, sp, #16
+    ret
+__objection_print_bool:
+    cbz x0, .Lprint_bool_false
+    adrp x0, .Lruntime_true
+    add x0, x0, :lo12:.Lruntime_true
+    b __objection_print_string
+.Lprint_bool_false:
+    adrp x0, .Lruntime_false
+    add x0, x0, :lo12:.Lruntime_false
+    b __objection_print_string
+__objection_write_digits:
+    mov x8, x2
+    sub sp, sp, #32
+    add x3, sp, #32
+    mov x4, x3
+    mov x5, #10
+.Lwrite_digits_loop:
+    udiv x6, x0, x5
+    msub x7, x6, x5, x0
+    add x7, x7, #48
+    strb w7, [x4, #-1]!
+    mov x0, x6
+    sub x1, x1, #1
+    cbnz x0, .Lwrite_digits_loop
+    cmp x1, #0
+    b.gt .Lwrite_digits_loop
+    sub x2, x3, x4
+    mov x1, x4
+    mov x0, x8
+    mov x8, #64
+    svc #0
+    add sp, sp, #32
+    ret
+__objection_print_int:
+    stp x29, x30, [sp, #-32]!
+    mov x29, sp
+    tbz x0, #63, .Lprint_int_positive
+    neg x0, x0
+    str x0, [x29, #16]
+    mov x0, #45
+    bl __objection_print_char
+    ldr x0, [x29, #16]
+.Lprint_int_positive:
+    mov x1, #1
+    mov x2, #1
+    bl __objection_write_digits
+    ldp x29, x30, [sp], #32
+    ret
+__objection_print_float:
+    stp x29, x30, [sp, #-32]!
+    mov x29, sp
+    fcmp d0, d0
+    b.vs .Lprint_float_nan
+    fmov x9, d0
+    tbz x9, #63, .Lprint_float_positive
+    fneg d0, d0
+    str d0, [x29, #16]
+    mov x0, #45
+    bl __objection_print_char
+    ldr d0, [x29, #16]
+.Lprint_float_positive:
+    ldr x9, =9218868437227405312
+    fmov d1, x9
+    fcmp d0, d1
+    b.eq .Lprint_float_inf
+    ldr x9, =4876203697187506176
+    fmov d1, x9
+    fmov d2, #10.0
+    mov x11, #0
+.Lprint_float_scale:
+    fcmp d0, d1
+    b.lt .Lprint_float_scaled
+    fdiv d0, d0, d2
+    add x11, x11, #1
+    b .Lprint_float_scale
+.Lprint_float_scaled:
+    fcvtzu x0, d0
+    ucvtf d1, x0
+    fsub d1, d0, d1
+    ldr x9, =4696837146684686336
+    fmov d2, x9
+    fmul d1, d1, d2
+    fcvtau x1, d1
+    ldr x9, =1000000
+    cmp x1, x9
+    b.lo .Lprint_float_rounded
+    mov x1, #0
+    add x0, x0, #1
+.Lprint_float_rounded:
+    stp x1, x11, [x29, #16]
+    mov x1, #1
+    mov x2, #1
+    bl __objection_write_digits
+.Lprint_float_zeros:
+    ldr x11, [x29, #24]
+    cbz x11, .Lprint_float_point
+    sub x11, x11, #1
+    str x11, [x29, #24]
+    mov x0, #48
+    bl __objection_print_char
+    b .Lprint_float_zeros
+.Lprint_float_point:
+    mov x0, #46
+    bl __objection_print_char
+    ldr x0, [x29, #16]
+    mov x1, #6
+    mov x2, #1
+    bl __objection_write_digits
+    b .Lprint_float_end
+.Lprint_float_nan:
+    adrp x0, .Lruntime_nan
+    add x0, x0, :lo12:.Lruntime_nan
+    bl __objection_print_string
+    b .Lprint_float_end
+.Lprint_float_inf:
+    adrp x0, .Lruntime_inf
+    add x0, x0, :lo12:.Lruntime_inf
+    bl __objection_print_string
+.Lprint_float_end:
+    ldp x29, x30, [sp], #32
+    ret
+__objection_out_of_bounds:
+    mov x19, x0
+    mov x20, x1
+    adrp x1, .Lruntime_out_of_bounds
+    add x1, x1, :lo12:.Lruntime_out_of_bounds
+    ldr x2, [x1], #8
+    mov x0, #2
+    mov x8, #64
+    svc #0
+    tbz x19, #63, .Lout_of_bounds_positive
+    adrp x1, .Lruntime_minus
+    add x1, x1, :lo12:.Lruntime_minus
+    ldr x2, [x1], #8
+    mov x0, #2
+    mov x8, #64
+    svc #0
+    neg x19, x19
+.Lout_of_bounds_positive:
+    mov x0, x19
+    mov x1, #1
+    mov x2, #2
+    bl __objection_write_digits
+    adrp x1, .Lruntime_out_of_bounds_len
+    add x1, x1, :lo12:.Lruntime_out_of_bounds_len
+    ldr x2, [x1], #8
+    mov x0, #2
+    mov x8, #64
+    svc #0
+    mov x0, x20
+    mov x1, #1
+    mov x2, #2
+    bl __objection_write_digits
+    adrp x1, .Lruntime_out_of_bounds_end
+    add x1, x1, :lo12:.Lruntime_out_of_bounds_end
+    ldr x2, [x1], #8
+    mov x0, #2
+    mov x8, #64
+    svc #0
+    mov x0, #101
+    mov x8, #93
+    svc #0
+__objection_div_by_zero:
+    adrp x1, .Lruntime_div_by_zero
+    add x1, x1, :lo12:.Lruntime_div_by_zero
+    ldr x2, [x1], #8
+    mov x0, #2
+    mov x8, #64
+    svc #0
+    mov x0, #102
+    mov x8, #93
+    svc #0
+__objection_list_new:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    mov x0, #24
+    bl __objection_alloc
+    stp xzr, xzr, [x0]
+    str xzr, [x0, #16]
+    ldp x29, x30, [sp], #16
+    ret
+__objection_list_push:
+    ldp x9, x10, [x0]
+    cmp x9, x10
+    b.lo .Llist_push_room
+    stp x29, x30, [sp, #-32]!
+    mov x29, sp
+    stp x0, x1, [x29, #16]
+    lsl x10, x10, #1
+    mov x11, #4
+    cmp x10, x11
+    csel x10, x10, x11, hs
+    str x10, [x0, #8]
+    mul x0, x10, x1
+    bl __objection_alloc
+    ldp x9, x1, [x29, #16]
+    ldr x10, [x9]
+    mul x10, x10, x1
+    ldr x11, [x9, #16]
+    mov x12, x0
+.Llist_push_copy:
+    cbz x10, .Llist_push_copied
+    ldr x13, [x11], #8
+    str x13, [x12], #8
+    sub x10, x10, #8
+    b .Llist_push_copy
+.Llist_push_copied:
+    str x0, [x9, #16]
+    mov x0, x9
+    ldp x29, x30, [sp], #32
+.Llist_push_room:
+    ldr x9, [x0]
+    ldr x10, [x0, #16]
+    madd x11, x9, x1, x10
+    add x9, x9, #1
+    str x9, [x0]
+    mov x0, x11
+    ret
+__objection_list_pop:
+    ldr x9, [x0]
+    cbz x9, .Llist_pop_empty
+    sub x9, x9, #1
+    str x9, [x0]
+    ldr x10, [x0, #16]
+    madd x0, x9, x1, x10
+    ret
+.Llist_pop_empty:
+    adrp x1, .Lruntime_empty_pop
+    add x1, x1, :lo12:.Lruntime_empty_pop
+    ldr x2, [x1], #8
+    mov x0, #2
+    mov x8, #64
+    svc #0
+    mov x0, #101
+    mov x8, #93
+    svc #0
+.data
+.p2align 3
+.Lheap:
+    .quad 0
+    .quad 0
+.text
+.section .rodata
+.p2align 3
+.Lstring_29:
+    .quad 2
+    .byte 110, 111
+.p2align 3
+.Lstring_30:
+    .quad 3
+    .byte 121, 101, 115
+.p2align 3
+.Lruntime_true:
+    .quad 4
+    .byte 116, 114, 117, 101
+.p2align 3
+.Lruntime_false:
+    .quad 5
+    .byte 102, 97, 108, 115, 101
+.p2align 3
+.Lruntime_nan:
+    .quad 3
+    .byte 110, 97, 110
+.p2align 3
+.Lruntime_inf:
+    .quad 3
+    .byte 105, 110, 102
+.p2align 3
+.Lruntime_minus:
+    .quad 1
+    .byte 45
+.p2align 3
+.Lruntime_out_of_bounds:
+    .quad 13
+    .byte 101, 114, 114, 111, 114, 58, 32, 73, 110, 100, 101, 120, 32
+.p2align 3
+.Lruntime_out_of_bounds_len:
+    .quad 29
+    .byte 32, 105, 115, 32, 111, 117, 116, 32, 111, 102, 32, 98, 111, 117, 110, 100, 115, 32, 102, 111, 114, 32, 108, 101, 110, 103, 116, 104, 32
+.p2align 3
+.Lruntime_out_of_bounds_end:
+    .quad 2
+    .byte 46, 10
+.p2align 3
+.Lruntime_empty_pop:
+    .quad 38
+    .byte 101, 114, 114, 111, 114, 58, 32, 67, 97, 110, 110, 111, 116, 32, 112, 111, 112, 32, 102, 114, 111, 109, 32, 97, 110, 32, 101, 109, 112, 116, 121, 32, 108, 105, 115, 116, 46, 10
+.p2align 3
+.Lruntime_div_by_zero:
+    .quad 25
+    .byte 101, 114, 114, 111, 114, 58, 32, 68, 105, 118, 105, 115, 105, 111, 110, 32, 98, 121, 32, 122, 101, 114, 111, 46, 10
+.p2align 3
+.Lruntime_out_of_memory:
+    .quad 22
+    .byte 101, 114, 114, 111, 114, 58, 32, 79, 117, 116, 32, 111, 102, 32, 109, 101, 109, 111, 114, 121, 46, 10
+.p2align 3